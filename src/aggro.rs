@@ -6,6 +6,7 @@ use bevy::math::Vec2;
 use crate::level::AggroRange;
 use crate::movement::Speed;
 use crate::ship::Ship;
+use crate::status_effects::StatusEffects;
 
 #[derive(Component)]
 pub struct Aggro(pub Entity);
@@ -45,13 +46,14 @@ pub fn handle_aggro(
 }
 
 pub fn aggro_movement(
-    mut aggro_query: Query<(&Aggro, &Speed, &Transform, &mut Velocity)>,
+    mut aggro_query: Query<(&Aggro, &Speed, &Transform, &mut Velocity, Option<&StatusEffects>)>,
     transform_q: Query<&Transform>,
 ) {
-    for (aggro, speed, aggro_transform, mut velocity) in aggro_query.iter_mut() {
+    for (aggro, speed, aggro_transform, mut velocity, status) in aggro_query.iter_mut() {
         if let Ok(transform) = transform_q.get(aggro.0) {
             let difference = transform.translation - aggro_transform.translation;
-            velocity.linvel = Vec2::new(difference.x, difference.y).normalize_or_zero() * speed.0;
+            let multiplier = status.map_or(1., |status| status.speed_multiplier());
+            velocity.linvel = Vec2::new(difference.x, difference.y).normalize_or_zero() * speed.0 * multiplier;
         }
     }
 }
@@ -19,7 +19,7 @@ pub fn animation_system(
     }
 }
 
-#[derive(Component, Deref)]
+#[derive(Component, Deref, Clone)]
 pub struct Animation(pub benimator::Animation);
 
 #[derive(Default, Component, Deref, DerefMut)]
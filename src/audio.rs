@@ -1,7 +1,198 @@
-use bevy::prelude::{AssetServer, Res};
+use std::collections::{HashMap, HashSet};
+use bevy::asset::LoadState;
+use bevy::prelude::{AssetServer, Button, Changed, DetectChanges, EventReader, FromWorld, Handle, Interaction, Local, Query, Ref, Res, ResMut, Resource, Visibility, warn, With, World};
 use bevy_kira_audio::prelude::*;
+use crate::beams::{BeamUpEvent, InteractLightBeam};
+use crate::boost::Boost;
+use crate::damage::DamageTakenEvent;
+use crate::music::MusicTrack;
+use crate::settings::Settings;
+use crate::ship::Ship;
 
+#[derive(Resource)]
+pub struct MusicChannel;
 
-pub fn start_background_audio(asset_server: Res<AssetServer>, audio: Res<Audio>) {
-    audio.play(asset_server.load("Bob'sAdventure.mp3")).looped();
+#[derive(Resource)]
+pub struct SfxChannel;
+
+#[derive(Resource)]
+pub struct UiChannel;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Sound {
+    BeamUp,
+    BeamOn,
+    BeamOff,
+    Damage,
+    Boost,
+    ButtonHover,
+    ButtonClick,
+    GameOver,
+    OrganismChirp,
+    AggroScreech,
+    ArtifactHum,
+}
+
+impl Sound {
+    pub const ALL: [Sound; 11] = [
+        Sound::BeamUp,
+        Sound::BeamOn,
+        Sound::BeamOff,
+        Sound::Damage,
+        Sound::Boost,
+        Sound::ButtonHover,
+        Sound::ButtonClick,
+        Sound::GameOver,
+        Sound::OrganismChirp,
+        Sound::AggroScreech,
+        Sound::ArtifactHum,
+    ];
+
+    pub fn path(&self) -> &'static str {
+        match self {
+            Sound::BeamUp => "sounds/beam-up.mp3",
+            Sound::BeamOn => "sounds/beam-on.mp3",
+            Sound::BeamOff => "sounds/beam-off.mp3",
+            Sound::Damage => "sounds/damage.mp3",
+            Sound::Boost => "sounds/boost.mp3",
+            Sound::ButtonHover => "sounds/button-hover.mp3",
+            Sound::ButtonClick => "sounds/button-click.mp3",
+            Sound::GameOver => "sounds/game-over.mp3",
+            Sound::OrganismChirp => "sounds/organism-chirp.mp3",
+            Sound::AggroScreech => "sounds/aggro-screech.mp3",
+            Sound::ArtifactHum => "sounds/artifact-hum.mp3",
+        }
+    }
+}
+
+/// Every audio handle the game uses, loaded up front so missing files are reported once
+/// instead of silently queueing sounds that never play.
+#[derive(Resource)]
+pub struct SoundLibrary {
+    music: HashMap<MusicTrack, Handle<AudioSource>>,
+    sounds: HashMap<Sound, Handle<AudioSource>>,
+    missing: HashSet<&'static str>,
+}
+
+impl FromWorld for SoundLibrary {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        SoundLibrary {
+            music: MusicTrack::ALL.iter().map(|track| (*track, asset_server.load(track.path()))).collect(),
+            sounds: Sound::ALL.iter().map(|sound| (*sound, asset_server.load(sound.path()))).collect(),
+            missing: HashSet::new(),
+        }
+    }
+}
+
+impl SoundLibrary {
+    pub fn sound(&self, sound: Sound, asset_server: &AssetServer) -> Option<Handle<AudioSource>> {
+        self.sounds.get(&sound).and_then(|handle| loaded(handle, asset_server))
+    }
+
+    pub fn music(&self, track: MusicTrack, asset_server: &AssetServer) -> Option<Handle<AudioSource>> {
+        self.music.get(&track).and_then(|handle| loaded(handle, asset_server))
+    }
+
+    fn handles(&self) -> impl Iterator<Item = (&'static str, &Handle<AudioSource>)> {
+        self.music.iter().map(|(track, handle)| (track.path(), handle))
+            .chain(self.sounds.iter().map(|(sound, handle)| (sound.path(), handle)))
+    }
+}
+
+fn loaded(handle: &Handle<AudioSource>, asset_server: &AssetServer) -> Option<Handle<AudioSource>> {
+    (asset_server.get_load_state(handle) == LoadState::Loaded).then(|| handle.clone())
+}
+
+fn play<T: Resource>(channel: &AudioChannel<T>, library: &SoundLibrary, asset_server: &AssetServer, sound: Sound) {
+    if let Some(handle) = library.sound(sound, asset_server) {
+        channel.play(handle);
+    }
+}
+
+pub fn report_missing_audio(asset_server: Res<AssetServer>, mut library: ResMut<SoundLibrary>) {
+    let failed: Vec<&'static str> = library.handles()
+        .filter(|(path, handle)| {
+            !library.missing.contains(path) && asset_server.get_load_state(*handle) == LoadState::Failed
+        })
+        .map(|(path, _)| path)
+        .collect();
+    for path in failed {
+        warn!("Audio asset {} could not be loaded; it will be skipped", path);
+        library.missing.insert(path);
+    }
+}
+
+pub fn apply_volume_settings(
+    settings: Res<Settings>,
+    sfx: Res<AudioChannel<SfxChannel>>,
+    ui: Res<AudioChannel<UiChannel>>,
+) {
+    if !settings.is_changed() { return; }
+    sfx.set_volume(settings.sfx_level() as f64);
+    ui.set_volume(settings.sfx_level() as f64);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn play_gameplay_sounds(
+    mut was_boosting: Local<bool>,
+    asset_server: Res<AssetServer>,
+    library: Res<SoundLibrary>,
+    sfx: Res<AudioChannel<SfxChannel>>,
+    mut beam_up_events: EventReader<BeamUpEvent>,
+    mut damage_events: EventReader<DamageTakenEvent>,
+    beam_q: Query<Ref<Visibility>, With<InteractLightBeam>>,
+    boost_q: Query<&Boost, With<Ship>>,
+) {
+    for _ in beam_up_events.iter() {
+        play(&sfx, &library, &asset_server, Sound::BeamUp);
+    }
+    if damage_events.iter().count() > 0 {
+        play(&sfx, &library, &asset_server, Sound::Damage);
+    }
+    for visibility in beam_q.iter() {
+        if !visibility.is_changed() || visibility.is_added() { continue; }
+        let sound = if *visibility == Visibility::Hidden { Sound::BeamOff } else { Sound::BeamOn };
+        play(&sfx, &library, &asset_server, sound);
+    }
+    let boosting = boost_q.iter().any(|boost| boost.active);
+    if boosting && !*was_boosting {
+        play(&sfx, &library, &asset_server, Sound::Boost);
+    }
+    *was_boosting = boosting;
+}
+
+pub fn play_button_sounds(
+    asset_server: Res<AssetServer>,
+    library: Res<SoundLibrary>,
+    ui: Res<AudioChannel<UiChannel>>,
+    interaction_q: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
+) {
+    for interaction in interaction_q.iter() {
+        match interaction {
+            Interaction::Hovered => play(&ui, &library, &asset_server, Sound::ButtonHover),
+            Interaction::Clicked => play(&ui, &library, &asset_server, Sound::ButtonClick),
+            Interaction::None => {}
+        }
+    }
+}
+
+pub fn play_game_over_sound(
+    asset_server: Res<AssetServer>,
+    library: Res<SoundLibrary>,
+    sfx: Res<AudioChannel<SfxChannel>>,
+) {
+    play(&sfx, &library, &asset_server, Sound::GameOver);
+}
+
+#[cfg(test)]
+mod audio_tests {
+    use super::*;
+
+    #[test]
+    fn it_ships_every_sound() {
+        for sound in Sound::ALL {
+            assert!(std::path::Path::new("assets").join(sound.path()).exists(), "{} is missing", sound.path());
+        }
+    }
 }
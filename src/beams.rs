@@ -1,11 +1,12 @@
-use bevy::prelude::{Commands, Component, Entity, EventWriter, KeyCode, MouseButton, Query, Res, ResMut, Resource, Visibility, With};
+use std::time::Duration;
+use bevy::prelude::{Changed, Children, Color, Commands, Component, Entity, EventReader, EventWriter, GlobalTransform, MouseButton, Query, Res, ResMut, Resource, Sprite, Time, Timer, TimerMode, Transform, Visibility, With};
 use bevy::input::Input;
+use bevy_rapier2d::dynamics::ExternalForce;
 use bevy_rapier2d::geometry::{ActiveEvents, Collider, Sensor};
-use bevy::math::Vec2;
+use bevy::math::{Vec2, Vec3};
 use crate::animation;
 use crate::animation::Animation;
-use crate::level::{Inventory, Item};
-use crate::movement::Speed;
+use crate::level::{Element251, Inventory, Item, ItemKind, Quantity};
 use crate::ship::Ship;
 
 #[derive(Component)]
@@ -14,108 +15,502 @@ pub struct InteractLightBeam;
 #[derive(Resource)]
 pub struct UnderBeamItems(pub Vec<Entity>);
 
-pub struct BeamUpEvent(pub Entity);
+/// An item reached the ship and all of its units, the `u32`, went into the inventory.
+pub struct BeamUpEvent(pub Entity, pub u32);
+
+pub const TRACTOR_PICKUP_DISTANCE: f32 = 24.;
+const TRACTOR_STRUGGLE_FREQUENCY: f32 = 6.;
+const ELEMENT_251_ENERGY_BONUS: f32 = 25.;
+
+#[derive(Component)]
+pub struct TractorResistance(pub f32);
+
+pub struct BeamLevelConfig {
+    pub color: Color,
+    pub range: f32,
+    pub width: f32,
+    pub pull: f32,
+    pub max_items: usize,
+    pub upgrade_cost: &'static [(ItemKind, usize)],
+}
+
+/// Every level shares the level 1 beam sprite, tinted with `color`; range and width scale the
+/// sprite and collider.
+/// `upgrade_cost` is what it takes to reach the next level.
+pub const BEAM_LEVELS: [BeamLevelConfig; 3] = [
+    BeamLevelConfig {
+        color: Color::WHITE,
+        range: 1.,
+        width: 1.,
+        pull: 400.,
+        max_items: 1,
+        upgrade_cost: &[(ItemKind::Gold, 2), (ItemKind::Water, 1)],
+    },
+    BeamLevelConfig {
+        color: Color::rgb(0.7, 1., 0.8),
+        range: 1.3,
+        width: 1.4,
+        pull: 550.,
+        max_items: 2,
+        upgrade_cost: &[(ItemKind::Gold, 3), (ItemKind::Element251, 1)],
+    },
+    BeamLevelConfig {
+        color: Color::rgb(1., 0.7, 0.9),
+        range: 1.6,
+        width: 1.8,
+        pull: 750.,
+        max_items: 4,
+        upgrade_cost: &[],
+    },
+];
+
+const BEAM_APEX_OFFSET: f32 = 17.;
+const BEAM_APEX_DEPTH: f32 = 55.;
+
+#[derive(Component, Default)]
+pub struct BeamLevel(pub usize);
+
+impl BeamLevel {
+    pub fn config(&self) -> &'static BeamLevelConfig {
+        &BEAM_LEVELS[self.0.min(BEAM_LEVELS.len() - 1)]
+    }
+
+    pub fn is_max(&self) -> bool {
+        self.0 + 1 >= BEAM_LEVELS.len()
+    }
+
+    pub fn try_upgrade(&mut self, inventory: &mut Inventory) -> bool {
+        if self.is_max() || !inventory.spend(self.config().upgrade_cost) { return false; }
+        self.0 += 1;
+        true
+    }
+}
+
+pub fn apply_beam_level(
+    ship_q: Query<(&BeamLevel, &Children), Changed<BeamLevel>>,
+    mut beam_q: Query<(&mut Transform, &mut Sprite), With<InteractLightBeam>>,
+) {
+    for (level, children) in ship_q.iter() {
+        let config = level.config();
+        for child in children.iter() {
+            if let Ok((mut transform, mut sprite)) = beam_q.get_mut(*child) {
+                transform.translation.x = BEAM_APEX_OFFSET + BEAM_APEX_DEPTH * config.range;
+                transform.scale = Vec3::new(config.range, config.width, 1.);
+                sprite.color = config.color;
+            }
+        }
+    }
+}
 
 pub fn beam_input(
     mut commands: Commands,
     mouse_input: Res<Input<MouseButton>>,
     beam_q: Query<Entity, With<InteractLightBeam>>,
-    ship_q: Query<Entity, With<Ship>>,
+    ship_q: Query<(Entity, Option<&BeamEnergy>), With<Ship>>,
 ) {
     if mouse_input.just_pressed(MouseButton::Left) {
-        for beam in beam_q.iter() {
-            commands.entity(beam).insert(
-                (Visibility::Visible,
-                 Collider::triangle(Vec2::new(-55., 0.),
-                                    Vec2::new(56., 18.),
-                                    Vec2::new(56., -18.)),
-                 ActiveEvents::COLLISION_EVENTS,
-                 Sensor));
-        }
-        for ship in ship_q.iter() {
-            animation::add_blinking_animation(&mut commands, ship);
+        for (ship, energy) in ship_q.iter() {
+            if energy.is_none_or(|energy| energy.can_fire()) {
+                turn_beam_on(&mut commands, &beam_q, ship);
+            }
         }
     }
     if mouse_input.just_released(MouseButton::Left) {
-        for beam in beam_q.iter() {
-            commands.entity(beam).insert(Visibility::Hidden);
-            commands.entity(beam).remove::<Collider>().remove::<Sensor>();
+        for (ship, _) in ship_q.iter() {
+            turn_beam_off(&mut commands, &beam_q, ship);
+        }
+    }
+}
+
+fn turn_beam_on(commands: &mut Commands, beam_q: &Query<Entity, With<InteractLightBeam>>, ship: Entity) {
+    for beam in beam_q.iter() {
+        commands.entity(beam).insert(
+            (Visibility::Visible,
+             Collider::triangle(Vec2::new(-BEAM_APEX_DEPTH, 0.),
+                                Vec2::new(56., 18.),
+                                Vec2::new(56., -18.)),
+             ActiveEvents::COLLISION_EVENTS,
+             Sensor));
+    }
+    animation::add_blinking_animation(commands, ship);
+}
+
+fn turn_beam_off(commands: &mut Commands, beam_q: &Query<Entity, With<InteractLightBeam>>, ship: Entity) {
+    for beam in beam_q.iter() {
+        commands.entity(beam).insert(Visibility::Hidden);
+        commands.entity(beam).remove::<Collider>().remove::<Sensor>();
+    }
+    commands.entity(ship).remove::<Animation>();
+}
+
+#[derive(Component)]
+pub struct BeamEnergy {
+    pub energy: f32,
+    pub max_energy: f32,
+    pub drain_rate: f32,
+    pub regen_rate: f32,
+    pub lockout: Timer,
+}
+
+impl Default for BeamEnergy {
+    fn default() -> Self {
+        let mut lockout = Timer::new(Duration::from_secs(3), TimerMode::Once);
+        lockout.tick(lockout.duration());
+        BeamEnergy {
+            energy: 100.,
+            max_energy: 100.,
+            drain_rate: 20.,
+            regen_rate: 25.,
+            lockout,
+        }
+    }
+}
+
+impl BeamEnergy {
+    pub fn can_fire(&self) -> bool {
+        self.lockout.finished() && self.energy > 0.
+    }
+
+    pub fn overheated(&self) -> bool {
+        !self.lockout.finished()
+    }
+
+    pub fn energy_percent(&self) -> f32 {
+        if self.max_energy <= 0. { return 0.; }
+        self.energy / self.max_energy * 100.
+    }
+
+    pub fn cool_down(&mut self) {
+        let remaining = self.lockout.remaining();
+        self.lockout.tick(remaining);
+        self.energy = self.max_energy;
+    }
+
+    pub fn increase_max(&mut self, amount: f32) {
+        self.max_energy += amount;
+        self.energy += amount;
+    }
+
+    /// Returns true on the frame the beam overheats.
+    pub fn update(&mut self, beam_on: bool, delta: Duration) -> bool {
+        self.lockout.tick(delta);
+        let seconds = delta.as_secs_f32();
+        if beam_on && self.lockout.finished() {
+            self.energy = (self.energy - self.drain_rate * seconds).max(0.);
+            if self.energy <= 0. {
+                self.lockout.reset();
+                return true;
+            }
+        } else if !beam_on && self.lockout.finished() {
+            self.energy = (self.energy + self.regen_rate * seconds).min(self.max_energy);
         }
-        for ship in ship_q.iter() {
-            commands.entity(ship).remove::<Animation>();
+        false
+    }
+}
+
+pub fn beam_energy(
+    mut commands: Commands,
+    time: Res<Time>,
+    beam_q: Query<Entity, With<InteractLightBeam>>,
+    visibility_q: Query<&Visibility, With<InteractLightBeam>>,
+    mut ship_q: Query<(Entity, &mut BeamEnergy), With<Ship>>,
+) {
+    let beam_on = visibility_q.iter().any(|visibility| *visibility == Visibility::Visible);
+    for (ship, mut energy) in ship_q.iter_mut() {
+        if energy.update(beam_on, time.delta()) {
+            turn_beam_off(&mut commands, &beam_q, ship);
+        }
+    }
+}
+
+pub fn element_increases_beam_energy(
+    mut beam_up_event: EventReader<BeamUpEvent>,
+    element_q: Query<&Element251>,
+    mut ship_q: Query<&mut BeamEnergy, With<Ship>>,
+) {
+    for ev in beam_up_event.iter() {
+        if element_q.get(ev.0).is_err() { continue; }
+        for mut energy in ship_q.iter_mut() {
+            energy.increase_max(ELEMENT_251_ENERGY_BONUS);
         }
     }
 }
 
+#[allow(clippy::type_complexity)]
+pub fn tractor_beam(
+    time: Res<Time>,
+    beam_q: Query<&Visibility, With<InteractLightBeam>>,
+    ship_q: Query<(&GlobalTransform, Option<&BeamLevel>, Option<&Inventory>), With<Ship>>,
+    under_beam: Res<UnderBeamItems>,
+    mut item_q: Query<(Entity, &GlobalTransform, &mut ExternalForce, Option<&TractorResistance>, Option<&Quantity>), With<Item>>,
+) {
+    let beam_on = beam_q.iter().any(|visibility| *visibility == Visibility::Visible);
+    let ship = ship_q.get_single().map(|(transform, level, inventory)| {
+        (transform.translation().truncate(), level.map_or(&BEAM_LEVELS[0], |level| level.config()), inventory)
+    });
+    // Items that wouldn't fit in the inventory are left where they are, and don't use up a slot.
+    let pulled: Vec<Entity> = match ship {
+        Ok((_, config, inventory)) if beam_on => under_beam.0.iter()
+            .filter(|item| {
+                let units = item_q.get(**item).ok().and_then(|(.., quantity)| quantity).map_or(1, |quantity| quantity.0);
+                inventory.is_none_or(|inventory| inventory.has_room(units))
+            })
+            .take(config.max_items)
+            .copied()
+            .collect(),
+        _ => vec![],
+    };
+    for (item, item_transform, mut external_force, resistance, _) in item_q.iter_mut() {
+        external_force.force = match ship {
+            Ok((ship_position, config, _)) if pulled.contains(&item) => {
+                tractor_force(
+                    item_transform.translation().truncate(),
+                    ship_position,
+                    config.pull,
+                    resistance.map_or(0., |resistance| resistance.0),
+                    time.elapsed_seconds(),
+                )
+            }
+            _ => Vec2::ZERO,
+        };
+    }
+}
+
+pub fn tractor_force(from: Vec2, to: Vec2, pull: f32, resistance: f32, elapsed_seconds: f32) -> Vec2 {
+    let direction = (to - from).normalize_or_zero();
+    let struggle = resistance * (0.5 + 0.5 * (elapsed_seconds * TRACTOR_STRUGGLE_FREQUENCY).sin());
+    direction * (pull - struggle)
+}
+
+pub fn tractor_collect(
+    mut ship_q: Query<(&GlobalTransform, &mut Inventory), With<Ship>>,
+    item_q: Query<(&Item, &GlobalTransform, Option<&Quantity>)>,
+    mut ev_beam_up: EventWriter<BeamUpEvent>,
+    mut under_beam: ResMut<UnderBeamItems>,
+) {
+    let Ok((ship_transform, mut inventory)) = ship_q.get_single_mut() else { return; };
+    let ship_position = ship_transform.translation().truncate();
+    under_beam.0.retain(|beamed_entity| {
+        let Ok((item, item_transform, quantity)) = item_q.get(*beamed_entity) else { return true; };
+        let units = quantity.map_or(1, |quantity| quantity.0);
+        if item_transform.translation().truncate().distance(ship_position) > TRACTOR_PICKUP_DISTANCE
+            || !inventory.has_room(units) {
+            return true;
+        }
+        for _ in 0..units {
+            inventory.add(item);
+        }
+        ev_beam_up.send(BeamUpEvent(*beamed_entity, units));
+        false
+    });
+}
+
 #[cfg(test)]
 mod beam_tests {
-    use bevy::input::{ButtonState, InputPlugin};
-    use bevy::input::keyboard::KeyboardInput;
     use bevy::prelude::*;
     use super::*;
 
     #[test]
-    fn it_adds_to_inventory_when_beam_up() {
+    fn it_adds_to_inventory_when_pulled_to_ship() {
         let mut app = App::new();
         app.add_plugins(MinimalPlugins);
-        app.add_plugin(InputPlugin::default());
         app.add_event::<BeamUpEvent>();
-        app.insert_resource(UnderBeamItems(vec![Entity::from_raw(0)]));
-        app.add_system(beam_up);
+        app.add_system(tractor_collect);
 
+        let item = app.world.spawn((
+            Item { kind: ItemKind::Herbs, description: "Herbs".to_string(), texture: Handle::default() },
+            GlobalTransform::from_xyz(10., 0., 0.),
+        )).id();
+        app.insert_resource(UnderBeamItems(vec![item]));
         let player = app.world.spawn(
-            (Ship,Inventory::new(30))
+            (Ship, Inventory::new(30), GlobalTransform::default())
         ).id();
 
-        app.world.send_event(KeyboardInput {
-            scan_code: 0,
-            key_code: Option::from(KeyCode::Space),
-            state: ButtonState::Pressed,
-        });
-
         app.update();
 
-        let inventory = app.world.entity(player).get::<Inventory>();
+        let inventory = app.world.entity(player).get::<Inventory>().unwrap();
         assert_eq!(inventory.iter().len(), 1);
+        assert!(app.world.resource::<UnderBeamItems>().0.is_empty());
     }
-}
 
+    #[test]
+    fn it_adds_every_unit_of_a_bigger_pickup() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_event::<BeamUpEvent>();
+        app.add_system(tractor_collect);
+
+        let item = app.world.spawn((
+            Item { kind: ItemKind::Herbs, description: "Herbs".to_string(), texture: Handle::default() },
+            Quantity(3),
+            GlobalTransform::from_xyz(10., 0., 0.),
+        )).id();
+        app.insert_resource(UnderBeamItems(vec![item]));
+        let player = app.world.spawn(
+            (Ship, Inventory::new(30), GlobalTransform::default())
+        ).id();
 
-pub fn beam_up(
-    input: Res<Input<KeyCode>>,
-    item_query: Query<&Item>,
-    mut ev_beam_up: EventWriter<BeamUpEvent>,
-    mut inventory_query: Query<&mut Inventory, With<Ship>>,
-    mut under_beam: ResMut<UnderBeamItems>,
-) {
-    if !input.just_pressed(KeyCode::Space) || under_beam.0.is_empty() { return; }
-    let beamed_entity = under_beam.0.pop().unwrap();
-    if let Ok(item) = item_query.get(beamed_entity) {
-        if let Ok(mut inventory) = inventory_query.get_single_mut() {
-            inventory.add(item);
-            ev_beam_up.send(BeamUpEvent(beamed_entity));
-        }
+        app.update();
+
+        let inventory = app.world.entity(player).get::<Inventory>().unwrap();
+        assert_eq!(inventory.count(ItemKind::Herbs), 3);
     }
-}
 
-pub fn boost_input(
-    mut commands: Commands,
-    mut ship_q: Query<(Entity, &mut Speed), With<Ship>>,
-    key_input: Res<Input<KeyCode>>,
-) {
-    if key_input.just_pressed(KeyCode::LShift) {
-        for (ship, mut speed) in ship_q.iter_mut() {
-            speed.0 += 70.;
-            animation::add_blinking_animation(&mut commands, ship);
-        }
+    #[test]
+    fn it_leaves_pickups_that_do_not_fit_in_the_inventory() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_event::<BeamUpEvent>();
+        app.add_system(tractor_collect);
+
+        let item = app.world.spawn((
+            Item { kind: ItemKind::Herbs, description: "Herbs".to_string(), texture: Handle::default() },
+            Quantity(3),
+            GlobalTransform::from_xyz(10., 0., 0.),
+        )).id();
+        app.insert_resource(UnderBeamItems(vec![item]));
+        let player = app.world.spawn(
+            (Ship, Inventory::new(2), GlobalTransform::default())
+        ).id();
+
+        app.update();
+
+        let inventory = app.world.entity(player).get::<Inventory>().unwrap();
+        assert_eq!(inventory.iter().len(), 0);
+        assert_eq!(app.world.resource::<UnderBeamItems>().0, vec![item]);
+        assert!(app.world.resource::<Events<BeamUpEvent>>().is_empty());
     }
-    if key_input.just_released(KeyCode::LShift) {
-        for (ship, mut speed) in ship_q.iter_mut() {
-            speed.0 -= 70.;
-            commands.entity(ship).remove::<Animation>();
-        }
+
+    #[test]
+    fn it_skips_pickups_that_do_not_fit_when_choosing_what_to_pull() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_system(tractor_beam);
+
+        app.world.spawn((InteractLightBeam, Visibility::Visible));
+        app.world.spawn((Ship, BeamLevel::default(), Inventory::new(2), GlobalTransform::default()));
+        let too_big = app.world.spawn((
+            Item { kind: ItemKind::Gold, description: String::new(), texture: Handle::default() },
+            Quantity(5),
+            ExternalForce::default(),
+            GlobalTransform::from_xyz(50., 0., 0.),
+        )).id();
+        let fitting = app.world.spawn((
+            Item { kind: ItemKind::Water, description: String::new(), texture: Handle::default() },
+            ExternalForce::default(),
+            GlobalTransform::from_xyz(50., 0., 0.),
+        )).id();
+        app.insert_resource(UnderBeamItems(vec![too_big, fitting]));
+
+        app.update();
+
+        assert_eq!(app.world.entity(too_big).get::<ExternalForce>().unwrap().force, Vec2::ZERO);
+        assert_ne!(app.world.entity(fitting).get::<ExternalForce>().unwrap().force, Vec2::ZERO);
+    }
+
+    #[test]
+    fn it_keeps_pulling_items_that_have_not_reached_ship() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_event::<BeamUpEvent>();
+        app.add_system(tractor_collect);
+
+        let item = app.world.spawn((
+            Item { kind: ItemKind::Herbs, description: "Herbs".to_string(), texture: Handle::default() },
+            GlobalTransform::from_xyz(100., 0., 0.),
+        )).id();
+        app.insert_resource(UnderBeamItems(vec![item]));
+        let player = app.world.spawn(
+            (Ship, Inventory::new(30), GlobalTransform::default())
+        ).id();
+
+        app.update();
+
+        let inventory = app.world.entity(player).get::<Inventory>().unwrap();
+        assert_eq!(inventory.iter().len(), 0);
+        assert_eq!(app.world.resource::<UnderBeamItems>().0, vec![item]);
+    }
+
+    #[test]
+    fn it_pulls_resisting_items_more_weakly() {
+        let free = tractor_force(Vec2::new(100., 0.), Vec2::ZERO, 400., 0., 0.);
+        let resisting = tractor_force(Vec2::new(100., 0.), Vec2::ZERO, 400., 250., 0.);
+        assert_eq!(free, Vec2::new(-400., 0.));
+        assert!(resisting.x > free.x);
+    }
+
+    #[test]
+    fn it_overheats_and_locks_out_the_beam_when_energy_runs_out() {
+        let mut energy = BeamEnergy::default();
+        assert!(!energy.update(true, Duration::from_secs(2)));
+        assert_eq!(energy.energy, 60.);
+
+        assert!(energy.update(true, Duration::from_secs(3)));
+        assert!(energy.overheated());
+        assert!(!energy.can_fire());
+
+        energy.update(false, Duration::from_secs(1));
+        assert_eq!(energy.energy, 0.);
+        energy.update(false, Duration::from_secs(2));
+        assert!(!energy.overheated());
     }
-}
 
+    #[test]
+    fn it_regenerates_energy_up_to_increased_max() {
+        let mut energy = BeamEnergy {
+            energy: 50.,
+            ..Default::default()
+        };
+        energy.increase_max(25.);
+        assert_eq!(energy.energy, 75.);
+        energy.update(false, Duration::from_secs(10));
+        assert_eq!(energy.energy, 125.);
+    }
+
+    fn item(kind: ItemKind) -> Item {
+        Item { kind, description: String::new(), texture: Handle::default() }
+    }
+
+    #[test]
+    fn it_spends_inventory_to_upgrade_the_beam() {
+        let mut inventory = Inventory::new(30);
+        inventory.add(&item(ItemKind::Gold));
+        inventory.add(&item(ItemKind::Gold));
+        inventory.add(&item(ItemKind::Water));
+        inventory.add(&item(ItemKind::Herbs));
+        let mut level = BeamLevel::default();
 
+        assert!(level.try_upgrade(&mut inventory));
+        assert_eq!(level.0, 1);
+        assert_eq!(inventory.iter().len(), 1);
+        assert_eq!(inventory.count(ItemKind::Herbs), 1);
+    }
 
+    #[test]
+    fn it_refuses_items_once_the_inventory_is_full() {
+        let mut inventory = Inventory::new(1);
+        assert!(inventory.add(&item(ItemKind::Gold)));
+        assert!(!inventory.add(&item(ItemKind::Water)));
+        assert_eq!(inventory.iter().len(), 1);
+        assert!(!inventory.has_room(1));
+    }
+
+    #[test]
+    fn it_does_not_upgrade_without_every_ingredient() {
+        let mut inventory = Inventory::new(30);
+        inventory.add(&item(ItemKind::Gold));
+        inventory.add(&item(ItemKind::Gold));
+        let mut level = BeamLevel::default();
+
+        assert!(!level.try_upgrade(&mut inventory));
+        assert_eq!(level.0, 0);
+        assert_eq!(inventory.count(ItemKind::Gold), 2);
+    }
+
+    #[test]
+    fn it_stops_upgrading_at_max_level() {
+        let mut level = BeamLevel(BEAM_LEVELS.len() - 1);
+        assert!(level.is_max());
+        assert!(!level.try_upgrade(&mut Inventory::new(30)));
+    }
+}
@@ -0,0 +1,113 @@
+use std::time::Duration;
+use bevy::prelude::{Commands, Component, Entity, KeyCode, Query, Res, Time, Timer, TimerMode, With};
+use bevy::input::Input;
+use crate::animation;
+use crate::animation::Animation;
+use crate::settings::Settings;
+use crate::ship::Ship;
+
+#[derive(Component)]
+pub struct Boost {
+    pub fuel: f32,
+    pub max_fuel: f32,
+    pub drain_rate: f32,
+    pub regen_rate: f32,
+    pub multiplier: f32,
+    pub active: bool,
+    pub cooldown: Timer,
+}
+
+impl Default for Boost {
+    fn default() -> Self {
+        let mut cooldown = Timer::new(Duration::from_secs(2), TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        Boost {
+            fuel: 100.,
+            max_fuel: 100.,
+            drain_rate: 40.,
+            regen_rate: 15.,
+            multiplier: 1.75,
+            active: false,
+            cooldown,
+        }
+    }
+}
+
+impl Boost {
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.active { self.multiplier } else { 1. }
+    }
+
+    pub fn fuel_percent(&self) -> f32 {
+        if self.max_fuel <= 0. { return 0.; }
+        self.fuel / self.max_fuel * 100.
+    }
+
+    pub fn update(&mut self, wants_boost: bool, delta: Duration) {
+        self.cooldown.tick(delta);
+        let seconds = delta.as_secs_f32();
+        self.active = wants_boost && self.fuel > 0. && self.cooldown.finished();
+        if self.active {
+            self.fuel = (self.fuel - self.drain_rate * seconds).max(0.);
+            if self.fuel <= 0. {
+                self.active = false;
+                self.cooldown.reset();
+            }
+        } else if self.cooldown.finished() {
+            self.fuel = (self.fuel + self.regen_rate * seconds).min(self.max_fuel);
+        }
+    }
+}
+
+pub fn boost_input(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut ship_q: Query<(Entity, &mut Boost), With<Ship>>,
+    key_input: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+) {
+    for (ship, mut boost) in ship_q.iter_mut() {
+        let was_active = boost.active;
+        boost.update(key_input.pressed(settings.bindings.boost), time.delta());
+        if boost.active && !was_active {
+            animation::add_blinking_animation(&mut commands, ship);
+        } else if !boost.active && was_active {
+            commands.entity(ship).remove::<Animation>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod boost_tests {
+    use super::*;
+
+    #[test]
+    fn it_drains_fuel_and_goes_on_cooldown_when_empty() {
+        let mut boost = Boost::default();
+        boost.update(true, Duration::from_secs(1));
+        assert!(boost.active);
+        assert_eq!(boost.fuel, 60.);
+
+        boost.update(true, Duration::from_secs(2));
+        assert!(!boost.active);
+        assert_eq!(boost.fuel, 0.);
+
+        boost.update(true, Duration::from_secs(1));
+        assert!(!boost.active);
+        assert_eq!(boost.fuel, 0.);
+        assert_eq!(boost.speed_multiplier(), 1.);
+    }
+
+    #[test]
+    fn it_regenerates_fuel_when_not_boosting() {
+        let mut boost = Boost {
+            fuel: 50.,
+            ..Default::default()
+        };
+        boost.update(false, Duration::from_secs(2));
+        assert!(!boost.active);
+        assert_eq!(boost.fuel, 80.);
+        boost.update(false, Duration::from_secs(10));
+        assert_eq!(boost.fuel, boost.max_fuel);
+    }
+}
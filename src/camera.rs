@@ -1,26 +1,200 @@
-use bevy::prelude::{Added, Camera2dBundle, Changed, Color, Commands, Component, Query, Transform, With, Without};
+use std::time::Duration;
+use bevy::prelude::{default, Added, Assets, Axis, Camera2dBundle, Color, Commands, Component, EventReader, GamepadAxis, GamepadAxisType, Gamepads, GlobalTransform, Handle, OrthographicProjection, Query, Rect, Res, ResMut, Resource, Time, Timer, TimerMode, Transform, With, Without};
 use bevy::core_pipeline::clear_color::ClearColorConfig;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::math::{Vec2, Vec3};
+use bevy_ecs_ldtk::{LdtkLevel, LevelEvent};
+use crate::aggro::Aggro;
+use crate::boost::Boost;
+use crate::effects::CameraShake;
+use crate::settings::Settings;
 use crate::ship::Ship;
 
 #[derive(Component)]
 pub struct MainCamera;
 
-pub fn setup_main_camera(mut commands: Commands) {
+#[derive(Component)]
+pub struct CameraController {
+    pub damping: f32,
+    pub look_ahead: f32,
+    pub dead_zone: f32,
+}
+
+impl Default for CameraController {
+    fn default() -> Self {
+        CameraController {
+            damping: 6.,
+            look_ahead: 40.,
+            dead_zone: 12.,
+        }
+    }
+}
+
+/// Right stick values closer to centre than this are treated as drift and ignored.
+const STICK_DEADZONE: f32 = 0.15;
+/// How long zooming has to stop before the new zoom is written to the settings.
+const ZOOM_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Component)]
+pub struct CameraZoom {
+    /// The player's chosen zoom, applied live and copied to `Settings` once zooming stops.
+    pub level: f32,
+    pub min: f32,
+    pub max: f32,
+    pub smoothing: f32,
+    pub boost_zoom_out: f32,
+    pub chase_zoom_out: f32,
+    pub max_chasers: usize,
+    save_delay: Timer,
+}
+
+impl Default for CameraZoom {
+    fn default() -> Self {
+        CameraZoom {
+            level: Settings::default().zoom,
+            min: 0.35,
+            max: 1.,
+            smoothing: 4.,
+            boost_zoom_out: 0.2,
+            chase_zoom_out: 0.3,
+            max_chasers: 4,
+            save_delay: Timer::new(ZOOM_SAVE_DELAY, TimerMode::Once),
+        }
+    }
+}
+
+impl CameraZoom {
+    /// Projection scale for the player's chosen zoom, pulled out while boosting or being chased.
+    pub fn dynamic_zoom(&self, base: f32, boosting: bool, chasers: usize) -> f32 {
+        let mut zoom = base;
+        if boosting { zoom *= 1. + self.boost_zoom_out; }
+        zoom *= 1. + self.chase_zoom_out * chasers.min(self.max_chasers) as f32 / self.max_chasers as f32;
+        zoom.min(self.max * (1. + self.boost_zoom_out + self.chase_zoom_out))
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct LevelBounds(pub Option<Rect>);
+
+pub fn setup_main_camera(mut commands: Commands, settings: Res<Settings>) {
     let mut camera_bundle = Camera2dBundle::default();
     camera_bundle.camera_2d.clear_color = ClearColorConfig::Custom(Color::hex("#000").unwrap());
-    camera_bundle.projection.scale *= 0.55;
-    commands.spawn((MainCamera, camera_bundle));
+    camera_bundle.projection.scale = settings.zoom;
+    commands.spawn((MainCamera, CameraController::default(), CameraShake::default(), CameraZoom { level: settings.zoom, ..default() }, camera_bundle));
+}
+
+pub fn zoom_input(
+    time: Res<Time>,
+    mut wheel_events: EventReader<MouseWheel>,
+    gamepads: Res<Gamepads>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut camera_q: Query<&mut CameraZoom, With<MainCamera>>,
+    mut settings: ResMut<Settings>,
+) {
+    let Ok(mut zoom) = camera_q.get_single_mut() else { return; };
+    let mut delta = 0.;
+    for wheel in wheel_events.iter() {
+        delta -= match wheel.unit {
+            MouseScrollUnit::Line => wheel.y * 0.05,
+            MouseScrollUnit::Pixel => wheel.y * 0.0005,
+        };
+    }
+    for gamepad in gamepads.iter() {
+        let stick = gamepad_axes.get(GamepadAxis::new(gamepad, GamepadAxisType::RightStickY)).unwrap_or(0.);
+        if stick.abs() < STICK_DEADZONE { continue; }
+        delta -= stick * 0.5 * time.delta_seconds();
+    }
+    if delta != 0. {
+        zoom.level = (zoom.level + delta).clamp(zoom.min, zoom.max);
+        zoom.save_delay.reset();
+        return;
+    }
+    zoom.save_delay.tick(time.delta());
+    if zoom.save_delay.just_finished() && settings.zoom != zoom.level {
+        settings.zoom = zoom.level;
+    }
+}
+
+pub fn zoom_camera(
+    time: Res<Time>,
+    boost_q: Query<&Boost, With<Ship>>,
+    aggro_q: Query<(), With<Aggro>>,
+    mut camera_q: Query<(&mut OrthographicProjection, &CameraZoom), With<MainCamera>>,
+) {
+    let boosting = boost_q.iter().any(|boost| boost.active);
+    let chasers = aggro_q.iter().count();
+    for (mut projection, zoom) in camera_q.iter_mut() {
+        let target = zoom.dynamic_zoom(zoom.level, boosting, chasers);
+        let smoothing = 1. - (-zoom.smoothing * time.delta_seconds()).exp();
+        projection.scale += (target - projection.scale) * smoothing;
+    }
+}
+
+pub fn update_level_bounds(
+    mut level_events: EventReader<LevelEvent>,
+    level_q: Query<(&Handle<LdtkLevel>, &GlobalTransform)>,
+    levels: Res<Assets<LdtkLevel>>,
+    mut bounds: ResMut<LevelBounds>,
+) {
+    for level_event in level_events.iter() {
+        match level_event {
+            LevelEvent::Transformed(iid) => {
+                for (level_handle, level_transform) in level_q.iter() {
+                    let Some(ldtk_level) = levels.get(level_handle) else { continue; };
+                    if ldtk_level.level.iid != *iid { continue; }
+                    let min = level_transform.translation().truncate();
+                    let size = Vec2::new(ldtk_level.level.px_wid as f32, ldtk_level.level.px_hei as f32);
+                    bounds.0 = Some(Rect::from_corners(min, min + size));
+                }
+            }
+            LevelEvent::Despawned(_) => bounds.0 = None,
+            _ => {}
+        }
+    }
 }
 
 pub fn camera_follow_ship(
-    ship_q: Query<&Transform, (With<Ship>, Changed<Transform>, Without<MainCamera>)>,
-    mut camera_q: Query<&mut Transform, With<MainCamera>>,
+    time: Res<Time>,
+    bounds: Res<LevelBounds>,
+    ship_q: Query<&Transform, (With<Ship>, Without<MainCamera>)>,
+    mut camera_q: Query<(&mut Transform, &CameraController, &OrthographicProjection), With<MainCamera>>,
 ) {
-    for ship_transform in ship_q.iter() {
-        camera_move_to(&mut camera_q, ship_transform);
+    let Ok(ship_transform) = ship_q.get_single() else { return; };
+    let aim = (ship_transform.rotation * Vec3::X).truncate();
+    for (mut camera_transform, controller, projection) in camera_q.iter_mut() {
+        let target = ship_transform.translation.truncate() + aim * controller.look_ahead;
+        let mut position = follow_target(
+            camera_transform.translation.truncate(),
+            target,
+            controller,
+            time.delta_seconds(),
+        );
+        if let Some(bounds) = bounds.0 {
+            position = clamp_to_bounds(position, projection.area, bounds);
+        }
+        camera_transform.translation.x = position.x;
+        camera_transform.translation.y = position.y;
     }
 }
 
+pub fn follow_target(camera: Vec2, target: Vec2, controller: &CameraController, delta_seconds: f32) -> Vec2 {
+    let offset = target - camera;
+    if offset.length() <= controller.dead_zone { return camera; }
+    let desired = target - offset.normalize() * controller.dead_zone;
+    let smoothing = 1. - (-controller.damping * delta_seconds).exp();
+    camera.lerp(desired, smoothing)
+}
+
+/// Keeps the view inside `bounds`, centering it on any axis where the level is smaller than the view.
+pub fn clamp_to_bounds(position: Vec2, view: Rect, bounds: Rect) -> Vec2 {
+    let min = bounds.min - view.min;
+    let max = bounds.max - view.max;
+    Vec2::new(
+        if min.x > max.x { bounds.center().x } else { position.x.clamp(min.x, max.x) },
+        if min.y > max.y { bounds.center().y } else { position.y.clamp(min.y, max.y) },
+    )
+}
+
 pub fn position_camera_at_ship(
     added_ship_q: Query<&Transform, (Added<Ship>, Without<MainCamera>)>,
     mut camera_q: Query<&mut Transform, With<MainCamera>>,
@@ -41,3 +215,76 @@ fn camera_move_to(camera_q: &mut Query<&mut Transform, With<MainCamera>>, transf
         camera_transform.translation.y = transform.translation.y;
     }
 }
+
+#[cfg(test)]
+mod camera_tests {
+    use super::*;
+
+    #[test]
+    fn it_does_not_move_inside_the_dead_zone() {
+        let controller = CameraController::default();
+        let camera = Vec2::new(10., 10.);
+        assert_eq!(follow_target(camera, Vec2::new(15., 10.), &controller, 1. / 60.), camera);
+    }
+
+    #[test]
+    fn it_eases_toward_the_target() {
+        let controller = CameraController::default();
+        let position = follow_target(Vec2::ZERO, Vec2::new(100., 0.), &controller, 1. / 60.);
+        assert!(position.x > 0. && position.x < 100. - controller.dead_zone);
+        assert_eq!(position.y, 0.);
+    }
+
+    #[test]
+    fn it_clamps_the_view_to_the_level() {
+        let view = Rect::new(-100., -50., 100., 50.);
+        let bounds = Rect::new(0., 0., 1000., 500.);
+        assert_eq!(clamp_to_bounds(Vec2::new(-40., 600.), view, bounds), Vec2::new(100., 450.));
+        assert_eq!(clamp_to_bounds(Vec2::new(500., 250.), view, bounds), Vec2::new(500., 250.));
+    }
+
+    #[test]
+    fn it_centers_levels_smaller_than_the_view() {
+        let view = Rect::new(-100., -50., 100., 50.);
+        let bounds = Rect::new(0., 0., 150., 500.);
+        assert_eq!(clamp_to_bounds(Vec2::new(10., 200.), view, bounds).x, 75.);
+    }
+
+    #[test]
+    fn it_zooms_out_while_boosting_and_chased() {
+        let zoom = CameraZoom::default();
+        assert_eq!(zoom.dynamic_zoom(0.5, false, 0), 0.5);
+        assert!(zoom.dynamic_zoom(0.5, true, 0) > 0.5);
+        assert!(zoom.dynamic_zoom(0.5, true, 2) > zoom.dynamic_zoom(0.5, true, 0));
+        assert_eq!(zoom.dynamic_zoom(0.5, false, 4), zoom.dynamic_zoom(0.5, false, 40));
+    }
+
+    #[test]
+    fn it_saves_the_zoom_only_once_zooming_stops() {
+        let mut app = bevy::prelude::App::new();
+        app.add_plugins(bevy::prelude::MinimalPlugins);
+        app.add_event::<MouseWheel>();
+        app.init_resource::<Gamepads>();
+        app.init_resource::<Axis<GamepadAxis>>();
+        app.insert_resource(Settings::default());
+        app.add_system(zoom_input);
+        let camera = app.world.spawn((MainCamera, CameraZoom::default())).id();
+        let mut now = bevy::utils::Instant::now();
+        let mut step = |app: &mut bevy::prelude::App| {
+            now += Duration::from_millis(200);
+            app.insert_resource(bevy::time::TimeUpdateStrategy::ManualInstant(now));
+            app.update();
+        };
+        step(&mut app);
+
+        app.world.send_event(MouseWheel { unit: MouseScrollUnit::Line, x: 0., y: -1. });
+        step(&mut app);
+        let level = app.world.entity(camera).get::<CameraZoom>().unwrap().level;
+        assert!(level > Settings::default().zoom);
+        step(&mut app);
+        step(&mut app);
+        assert_eq!(app.world.resource::<Settings>().zoom, Settings::default().zoom);
+        step(&mut app);
+        assert_eq!(app.world.resource::<Settings>().zoom, level);
+    }
+}
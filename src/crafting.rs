@@ -0,0 +1,152 @@
+use crate::level::{Inventory, ItemKind};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RecipeEffect {
+    RestoreLifeSupport(f32),
+    RefillBoost,
+    CoolBeam,
+    ActivateArtifact(ItemKind),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RecipeOutput {
+    Item(ItemKind),
+    Effect(RecipeEffect),
+}
+
+pub struct Recipe {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub inputs: &'static [(ItemKind, usize)],
+    pub output: RecipeOutput,
+}
+
+pub struct ArtifactActivatedEvent(pub ItemKind);
+
+pub const RECIPES: [Recipe; 6] = [
+    Recipe {
+        name: "Herbal Tonic",
+        description: "Restores 4 Life Support.",
+        inputs: &[(ItemKind::Herbs, 2), (ItemKind::Water, 1)],
+        output: RecipeOutput::Effect(RecipeEffect::RestoreLifeSupport(4.)),
+    },
+    Recipe {
+        name: "Fuel Cell",
+        description: "Refills boost fuel.",
+        inputs: &[(ItemKind::Element251, 1), (ItemKind::Gold, 1)],
+        output: RecipeOutput::Effect(RecipeEffect::RefillBoost),
+    },
+    Recipe {
+        name: "Beam Coolant",
+        description: "Cools the beam and refills its energy.",
+        inputs: &[(ItemKind::Water, 2)],
+        output: RecipeOutput::Effect(RecipeEffect::CoolBeam),
+    },
+    Recipe {
+        name: "Synthesize Element251",
+        description: "Turns gold and an organism into Element251.",
+        inputs: &[(ItemKind::Gold, 3), (ItemKind::Organism, 1)],
+        output: RecipeOutput::Item(ItemKind::Element251),
+    },
+    Recipe {
+        name: "Activate Weapon Artifact",
+        description: "Radioactive weapon, capable of destruction.",
+        inputs: &[
+            (ItemKind::WeaponArtifact, 1),
+            (ItemKind::Gold, 2),
+            (ItemKind::Element251, 1),
+            (ItemKind::Water, 3),
+            (ItemKind::Organism, 5),
+        ],
+        output: RecipeOutput::Effect(RecipeEffect::ActivateArtifact(ItemKind::WeaponArtifact)),
+    },
+    Recipe {
+        name: "Activate Shield Artifact",
+        description: "Reflective capabilities.",
+        inputs: &[
+            (ItemKind::ShieldArtifact, 1),
+            (ItemKind::Water, 15),
+            (ItemKind::Element251, 1),
+            (ItemKind::Herbs, 3),
+            (ItemKind::Organism, 1),
+        ],
+        output: RecipeOutput::Effect(RecipeEffect::ActivateArtifact(ItemKind::ShieldArtifact)),
+    },
+];
+
+impl Recipe {
+    pub fn missing_ingredients(&self, inventory: &Inventory) -> Vec<(ItemKind, usize)> {
+        self.inputs.iter()
+            .filter_map(|(kind, count)| {
+                let owned = inventory.count(*kind);
+                if owned >= *count { None } else { Some((*kind, count - owned)) }
+            })
+            .collect()
+    }
+
+    pub fn can_craft(&self, inventory: &Inventory) -> bool {
+        inventory.can_afford(self.inputs)
+    }
+
+    /// Consumes every input, or nothing at all if any of them is missing.
+    pub fn craft(&self, inventory: &mut Inventory) -> Option<RecipeOutput> {
+        if !inventory.spend(self.inputs) { return None; }
+        Some(self.output)
+    }
+
+    pub fn ingredients_text(&self) -> String {
+        self.inputs.iter()
+            .map(|(kind, count)| format!("{} {}", count, kind.name()))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod crafting_tests {
+    use bevy::prelude::Handle;
+    use crate::level::Item;
+    use super::*;
+
+    fn inventory_with(items: &[(ItemKind, usize)]) -> Inventory {
+        let mut inventory = Inventory::new(50);
+        for (kind, count) in items {
+            for _ in 0..*count {
+                inventory.add(&Item { kind: *kind, description: String::new(), texture: Handle::default() });
+            }
+        }
+        inventory
+    }
+
+    #[test]
+    fn it_lists_missing_ingredients() {
+        let inventory = inventory_with(&[(ItemKind::Herbs, 1)]);
+        let tonic = &RECIPES[0];
+        assert!(!tonic.can_craft(&inventory));
+        assert_eq!(tonic.missing_ingredients(&inventory), vec![(ItemKind::Herbs, 1), (ItemKind::Water, 1)]);
+    }
+
+    #[test]
+    fn it_consumes_ingredients_when_crafting() {
+        let mut inventory = inventory_with(&[(ItemKind::Herbs, 3), (ItemKind::Water, 1)]);
+        let tonic = &RECIPES[0];
+        assert!(tonic.can_craft(&inventory));
+        assert_eq!(tonic.craft(&mut inventory), Some(RecipeOutput::Effect(RecipeEffect::RestoreLifeSupport(4.))));
+        assert_eq!(inventory.count(ItemKind::Herbs), 1);
+        assert_eq!(inventory.count(ItemKind::Water), 0);
+    }
+
+    #[test]
+    fn it_consumes_nothing_when_an_ingredient_is_missing() {
+        let mut inventory = inventory_with(&[(ItemKind::Gold, 3)]);
+        let synthesize = &RECIPES[3];
+        assert_eq!(synthesize.craft(&mut inventory), None);
+        assert_eq!(inventory.count(ItemKind::Gold), 3);
+    }
+
+    #[test]
+    fn it_only_matches_items_of_the_same_kind() {
+        let inventory = inventory_with(&[(ItemKind::Gold, 3), (ItemKind::YellowOrganism, 1)]);
+        assert!(!RECIPES[3].can_craft(&inventory));
+    }
+}
@@ -9,7 +9,7 @@ pub fn my_cursor_system(
     q_camera: Query<(&Camera, &GlobalTransform)>,
     mut indicator_q: Query<(&mut Transform, &GlobalTransform), With<Ship>>,
 ) {
-    if cursor_evr.len() == 0 || q_camera.is_empty() { return; }
+    if cursor_evr.is_empty() || q_camera.is_empty() { return; }
     let (camera, camera_transform) = q_camera.single();
     let wnd = windows_query.single();
     if let Some(screen_pos) = wnd.cursor_position() {
@@ -25,13 +25,21 @@ pub fn my_cursor_system(
 }
 
 fn get_cursor_translation(camera: &Camera, camera_transform: &GlobalTransform, wnd: &Window, screen_pos: Vec2) -> Vec2 {
-    let window_size = Vec2::new(wnd.width() as f32, wnd.height() as f32);
+    let window_size = Vec2::new(wnd.width(), wnd.height());
     let ndc = (screen_pos / window_size) * 2.0 - Vec2::ONE;
     let ndc_to_world = camera_transform.compute_matrix() * camera.projection_matrix().inverse();
     let world_pos = ndc_to_world.project_point3(ndc.extend(-1.0));
     world_pos.truncate()
 }
 
+/// Inverse of `get_cursor_translation`: window position (origin bottom-left) of a world point.
+pub fn get_screen_position(camera: &Camera, camera_transform: &GlobalTransform, wnd: &Window, world_pos: Vec2) -> Vec2 {
+    let window_size = Vec2::new(wnd.width(), wnd.height());
+    let world_to_ndc = camera.projection_matrix() * camera_transform.compute_matrix().inverse();
+    let ndc = world_to_ndc.project_point3(world_pos.extend(0.)).truncate();
+    (ndc + Vec2::ONE) / 2.0 * window_size
+}
+
 fn get_rotation_from_to(from: Vec2, to: Vec2) -> Quat {
     let diff = to - from;
     //TODO: May not be deterministic across computers
@@ -1,4 +1,4 @@
-use bevy::prelude::{Commands, Entity, EventReader, NextState, Query, Res, ResMut, Time, Timer, TimerMode, Visibility, With};
+use bevy::prelude::{Commands, Entity, EventReader, EventWriter, NextState, Query, Res, ResMut, Time, Timer, TimerMode, Visibility, With};
 use bevy_rapier2d::pipeline::CollisionEvent;
 use bevy::hierarchy::Children;
 use std::time::Duration;
@@ -7,15 +7,50 @@ use crate::beams::{InteractLightBeam, UnderBeamItems};
 use crate::level::{Damage, DamageCollider, Health, Item, ResourceNameplate};
 use crate::ship::Ship;
 
+/// What dealt a hit, as recorded in the run stats and shown on the game over screen.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DamageSource {
+    Organism,
+    HostileOrganism,
+    LifeSupport,
+    Poison,
+    Radiation,
+    Acid,
+    Storm,
+}
+
+impl DamageSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DamageSource::Organism => "Life Form",
+            DamageSource::HostileOrganism => "Hostile Life Form",
+            DamageSource::LifeSupport => "Life Support Failure",
+            DamageSource::Poison => "Poison",
+            DamageSource::Radiation => "Radiation",
+            DamageSource::Acid => "Acid",
+            DamageSource::Storm => "Storm",
+        }
+    }
+
+    /// A blow from something touching the ship, rather than damage ticking away over time.
+    pub fn is_hit(&self) -> bool {
+        matches!(self, DamageSource::Organism | DamageSource::HostileOrganism)
+    }
+}
+
+pub struct DamageTakenEvent(pub f32, pub DamageSource);
+
 pub fn handle_damage(
     time: Res<Time>,
     mut damage_q: Query<(&mut Damage, &mut Health), With<Ship>>,
     mut next_state: ResMut<NextState<AppState>>,
+    mut ev_damage_taken: EventWriter<DamageTakenEvent>,
 ) {
     for (mut damage, mut health) in damage_q.iter_mut() {
         damage.1.tick(time.delta());
         if damage.1.just_finished() {
             health.current -= damage.0;
+            ev_damage_taken.send(DamageTakenEvent(damage.0, damage.2));
         }
         if health.current <= 0. {
             next_state.set(AppState::GameOver);
@@ -83,11 +118,11 @@ pub fn handle_collision_damage(
             CollisionEvent::Started(e1, e2, _) => {
                 if let Ok(damage) = damage_q.get(*e1) {
                     if let Ok(ship) = ship_q.get(*e2) {
-                        commands.entity(ship).insert(Damage(damage.0, Timer::new(Duration::from_millis(150), TimerMode::Repeating)));
+                        commands.entity(ship).insert(Damage(damage.0, Timer::new(Duration::from_millis(150), TimerMode::Repeating), damage.1));
                     }
                 } else if let Ok(damage) = damage_q.get(*e2) {
                     if let Ok(ship) = ship_q.get(*e1) {
-                        commands.entity(ship).insert(Damage(damage.0, Timer::new(Duration::from_millis(150), TimerMode::Repeating)));
+                        commands.entity(ship).insert(Damage(damage.0, Timer::new(Duration::from_millis(150), TimerMode::Repeating), damage.1));
                     }
                 }
             }
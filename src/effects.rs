@@ -0,0 +1,232 @@
+use std::time::Duration;
+use bevy::prelude::{Component, EventReader, Local, Query, Res, ResMut, Resource, Time, Timer, TimerMode, Transform, With};
+use bevy::math::{Quat, Vec2};
+use crate::beams::BeamUpEvent;
+use crate::camera::MainCamera;
+use crate::crafting::ArtifactActivatedEvent;
+use crate::damage::DamageTakenEvent;
+use crate::level::{Item, ItemKind};
+
+#[derive(Resource)]
+pub struct ScreenShakeSettings {
+    pub enabled: bool,
+    /// Trauma per point of damage taken.
+    pub damage_trauma: f32,
+    pub artifact_trauma: f32,
+    pub organism_trauma: f32,
+    pub damage_hit_stop: Duration,
+    /// Only a hit after this long without one freezes the game, not every tick of a lasting contact.
+    pub hit_stop_cooldown: Duration,
+}
+
+impl Default for ScreenShakeSettings {
+    fn default() -> Self {
+        ScreenShakeSettings {
+            enabled: true,
+            damage_trauma: 0.2,
+            artifact_trauma: 0.8,
+            organism_trauma: 0.25,
+            damage_hit_stop: Duration::from_millis(50),
+            hit_stop_cooldown: Duration::from_secs(1),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct CameraShake {
+    pub trauma: f32,
+    pub decay: f32,
+    pub max_offset: f32,
+    pub max_angle: f32,
+    pub frequency: f32,
+    offset: Vec2,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        CameraShake {
+            trauma: 0.,
+            decay: 1.5,
+            max_offset: 12.,
+            max_angle: 0.05,
+            frequency: 18.,
+            offset: Vec2::ZERO,
+        }
+    }
+}
+
+impl CameraShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0., 1.);
+    }
+
+    pub fn decay(&mut self, delta_seconds: f32) {
+        self.trauma = (self.trauma - self.decay * delta_seconds).max(0.);
+    }
+
+    /// Offset and rotation for the current trauma, sampled from noise at `seconds`.
+    pub fn sample(&self, seconds: f32) -> (Vec2, f32) {
+        let shake = self.trauma * self.trauma;
+        let t = seconds * self.frequency;
+        (
+            Vec2::new(noise(1, t), noise(2, t)) * self.max_offset * shake,
+            noise(3, t) * self.max_angle * shake,
+        )
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct HitStop(pub Option<Timer>);
+
+/// Smooth value noise in [-1, 1].
+pub fn noise(seed: u32, t: f32) -> f32 {
+    let floor = t.floor();
+    let fraction = t - floor;
+    let smooth = fraction * fraction * (3. - 2. * fraction);
+    let a = hash(seed, floor as i32);
+    let b = hash(seed, floor as i32 + 1);
+    a + (b - a) * smooth
+}
+
+fn hash(seed: u32, x: i32) -> f32 {
+    let mut n = (x as u32).wrapping_mul(374_761_393) ^ seed.wrapping_mul(668_265_263);
+    n = (n ^ (n >> 13)).wrapping_mul(1_274_126_177);
+    n ^= n >> 16;
+    (n as f32 / u32::MAX as f32) * 2. - 1.
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn shake_on_gameplay_events(
+    mut last_hit: Local<Option<Duration>>,
+    time: Res<Time>,
+    settings: Res<ScreenShakeSettings>,
+    mut damage_events: EventReader<DamageTakenEvent>,
+    mut artifact_events: EventReader<ArtifactActivatedEvent>,
+    mut beam_up_events: EventReader<BeamUpEvent>,
+    item_q: Query<&Item>,
+    mut shake_q: Query<&mut CameraShake, With<MainCamera>>,
+    mut hit_stop: ResMut<HitStop>,
+) {
+    let mut trauma = 0.;
+    let mut hit = false;
+    for ev in damage_events.iter() {
+        trauma += ev.0 * settings.damage_trauma;
+        hit |= ev.1.is_hit();
+    }
+    if hit {
+        let now = time.raw_elapsed();
+        let first_hit = last_hit.is_none_or(|last_hit| now - last_hit >= settings.hit_stop_cooldown);
+        if settings.enabled && first_hit {
+            hit_stop.0 = Some(Timer::new(settings.damage_hit_stop, TimerMode::Once));
+        }
+        *last_hit = Some(now);
+    }
+    trauma += artifact_events.iter().count() as f32 * settings.artifact_trauma;
+    trauma += beam_up_events.iter()
+        .filter_map(|ev| item_q.get(ev.0).ok())
+        .filter(|item| matches!(item.kind, ItemKind::Organism | ItemKind::YellowOrganism))
+        .count() as f32 * settings.organism_trauma;
+    if !settings.enabled || trauma <= 0. { return; }
+    for mut shake in shake_q.iter_mut() {
+        shake.add_trauma(trauma);
+    }
+}
+
+pub fn remove_camera_shake(mut camera_q: Query<(&mut Transform, &CameraShake), With<MainCamera>>) {
+    for (mut transform, shake) in camera_q.iter_mut() {
+        transform.translation.x -= shake.offset.x;
+        transform.translation.y -= shake.offset.y;
+    }
+}
+
+pub fn apply_camera_shake(
+    time: Res<Time>,
+    settings: Res<ScreenShakeSettings>,
+    mut camera_q: Query<(&mut Transform, &mut CameraShake), With<MainCamera>>,
+) {
+    for (mut transform, mut shake) in camera_q.iter_mut() {
+        shake.decay(time.delta_seconds());
+        if !settings.enabled { shake.trauma = 0.; }
+        let (offset, angle) = shake.sample(time.raw_elapsed_seconds());
+        shake.offset = offset;
+        transform.translation.x += offset.x;
+        transform.translation.y += offset.y;
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
+
+pub fn update_hit_stop(mut time: ResMut<Time>, mut hit_stop: ResMut<HitStop>) {
+    let Some(timer) = hit_stop.0.as_mut() else { return; };
+    timer.tick(time.raw_delta());
+    if timer.finished() {
+        hit_stop.0 = None;
+        time.set_relative_speed(1.);
+    } else {
+        time.set_relative_speed(0.05);
+    }
+}
+
+#[cfg(test)]
+mod effects_tests {
+    use bevy::prelude::{default, App, Events, MinimalPlugins};
+    use crate::damage::DamageSource;
+    use super::*;
+
+    fn hit_stop_app(settings: ScreenShakeSettings) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_event::<DamageTakenEvent>();
+        app.add_event::<ArtifactActivatedEvent>();
+        app.add_event::<BeamUpEvent>();
+        app.insert_resource(settings);
+        app.init_resource::<HitStop>();
+        app.add_system(shake_on_gameplay_events);
+        app
+    }
+
+    fn take_damage(app: &mut App, source: DamageSource) -> bool {
+        app.world.resource_mut::<Events<DamageTakenEvent>>().send(DamageTakenEvent(1., source));
+        app.update();
+        app.world.resource_mut::<HitStop>().0.take().is_some()
+    }
+
+    #[test]
+    fn it_only_stops_for_the_first_hit_of_a_contact() {
+        let mut app = hit_stop_app(ScreenShakeSettings::default());
+        assert!(!take_damage(&mut app, DamageSource::Poison));
+        assert!(take_damage(&mut app, DamageSource::Organism));
+        assert!(!take_damage(&mut app, DamageSource::Organism));
+    }
+
+    #[test]
+    fn it_does_not_stop_when_screen_shake_is_off() {
+        let mut app = hit_stop_app(ScreenShakeSettings { enabled: false, ..default() });
+        assert!(!take_damage(&mut app, DamageSource::HostileOrganism));
+    }
+
+    #[test]
+    fn it_clamps_and_decays_trauma() {
+        let mut shake = CameraShake::default();
+        shake.add_trauma(0.7);
+        shake.add_trauma(0.7);
+        assert_eq!(shake.trauma, 1.);
+        shake.decay(0.2);
+        assert!((shake.trauma - 0.7).abs() < 1e-6);
+        shake.decay(10.);
+        assert_eq!(shake.trauma, 0.);
+    }
+
+    #[test]
+    fn it_does_not_shake_without_trauma() {
+        let shake = CameraShake::default();
+        assert_eq!(shake.sample(3.2), (Vec2::ZERO, 0.));
+    }
+
+    #[test]
+    fn it_keeps_noise_in_range() {
+        for step in 0..1000 {
+            let value = noise(7, step as f32 * 0.137);
+            assert!((-1. ..=1.).contains(&value));
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::EntityInstance;
+use bevy_ecs_ldtk::ldtk::FieldValue;
+
+/// Typed access to the custom fields a level designer set on an LDtk entity.
+///
+/// Every getter returns `None` when the field is missing or left empty, so callers can fall back
+/// to the entity type's default with `unwrap_or`. Values the game can't use are ignored the same
+/// way, with a warning naming the entity and field.
+pub struct EntityFields<'a> {
+    instance: &'a EntityInstance,
+}
+
+impl<'a> EntityFields<'a> {
+    pub fn new(instance: &'a EntityInstance) -> Self {
+        EntityFields { instance }
+    }
+
+    fn read<T>(&self, identifier: &str, parse: impl FnOnce(&'a FieldValue) -> Result<Option<T>, String>) -> Option<T> {
+        let field = self.instance.field_instances.iter().find(|field| field.identifier == identifier)?;
+        parse(&field.value).unwrap_or_else(|problem| {
+            warn!(
+                "{} at ({}, {}): ignoring field \"{}\", {}",
+                self.instance.identifier, self.instance.px.x, self.instance.px.y, identifier, problem,
+            );
+            None
+        })
+    }
+
+    fn number(value: &FieldValue) -> Result<Option<f32>, String> {
+        let number = match value {
+            FieldValue::Float(value) => *value,
+            FieldValue::Int(value) => value.map(|value| value as f32),
+            other => return Err(format!("expected a number but found {:?}", other)),
+        };
+        match number {
+            Some(number) if !number.is_finite() => Err(format!("{} is not a finite number", number)),
+            Some(number) if number < 0. => Err(format!("{} is negative", number)),
+            _ => Ok(number),
+        }
+    }
+
+    /// A number above zero, for sizes and amounts that make no sense when empty.
+    pub fn positive_float(&self, identifier: &str) -> Option<f32> {
+        self.read(identifier, |value| match Self::number(value)? {
+            Some(0.) => Err("it must be greater than 0".to_string()),
+            number => Ok(number),
+        })
+    }
+
+    /// A whole number of at least one.
+    pub fn count(&self, identifier: &str) -> Option<u32> {
+        self.read(identifier, |value| match value {
+            FieldValue::Int(Some(count)) if *count < 1 => Err(format!("{} is less than 1", count)),
+            FieldValue::Int(count) => Ok(count.map(|count| count as u32)),
+            other => Err(format!("expected a whole number but found {:?}", other)),
+        })
+    }
+
+    /// An LDtk enum value, mapped with `parse` (usually the Rust enum's `from_identifier`).
+    pub fn enum_value<T>(&self, identifier: &str, parse: impl FnOnce(&str) -> Option<T>) -> Option<T> {
+        self.read(identifier, |value| match value {
+            FieldValue::Enum(Some(name)) => parse(name).map(Some).ok_or_else(|| format!("unknown value \"{}\"", name)),
+            FieldValue::Enum(None) => Ok(None),
+            other => Err(format!("expected an enum value but found {:?}", other)),
+        })
+    }
+}
+
+#[cfg(test)]
+pub fn field_instance(identifier: &str, value: FieldValue) -> bevy_ecs_ldtk::ldtk::FieldInstance {
+    bevy_ecs_ldtk::ldtk::FieldInstance {
+        identifier: identifier.to_string(),
+        tile: None,
+        field_instance_type: String::new(),
+        value,
+        def_uid: 0,
+        real_editor_values: vec![],
+    }
+}
+
+#[cfg(test)]
+mod entity_fields_tests {
+    use super::*;
+
+    fn instance(fields: Vec<(&str, FieldValue)>) -> EntityInstance {
+        EntityInstance {
+            identifier: "Organism".to_string(),
+            field_instances: fields.into_iter().map(|(identifier, value)| field_instance(identifier, value)).collect(),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn it_reads_the_fields_a_designer_set() {
+        let instance = instance(vec![
+            ("Speed", FieldValue::Float(Some(120.))),
+            ("Damage", FieldValue::Int(Some(3))),
+            ("Quantity", FieldValue::Int(Some(4))),
+            ("Kind", FieldValue::Enum(Some("Big".to_string()))),
+        ]);
+        let fields = EntityFields::new(&instance);
+        assert_eq!(fields.positive_float("Speed"), Some(120.));
+        assert_eq!(fields.positive_float("Damage"), Some(3.));
+        assert_eq!(fields.count("Quantity"), Some(4));
+        assert_eq!(fields.enum_value("Kind", |name| (name == "Big").then_some(2)), Some(2));
+    }
+
+    #[test]
+    fn it_falls_back_when_a_field_is_missing_or_empty() {
+        let instance = instance(vec![("Speed", FieldValue::Float(None)), ("Quantity", FieldValue::Int(None))]);
+        let fields = EntityFields::new(&instance);
+        assert_eq!(fields.positive_float("Speed"), None);
+        assert_eq!(fields.count("Quantity"), None);
+        assert_eq!(fields.positive_float("Damage"), None);
+    }
+
+    #[test]
+    fn it_ignores_malformed_values() {
+        let instance = instance(vec![
+            ("Speed", FieldValue::Float(Some(-5.))),
+            ("Health", FieldValue::Float(Some(0.))),
+            ("Damage", FieldValue::String(Some("lots".to_string()))),
+            ("Radius", FieldValue::Float(Some(f32::NAN))),
+            ("Quantity", FieldValue::Int(Some(0))),
+            ("Kind", FieldValue::Enum(Some("Huge".to_string()))),
+        ]);
+        let fields = EntityFields::new(&instance);
+        assert_eq!(fields.positive_float("Speed"), None);
+        assert_eq!(fields.positive_float("Health"), None);
+        assert_eq!(fields.positive_float("Damage"), None);
+        assert_eq!(fields.positive_float("Radius"), None);
+        assert_eq!(fields.count("Quantity"), None);
+        assert_eq!(fields.enum_value("Kind", |name| (name == "Big").then_some(2)), None);
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use bevy::prelude::*;
+use benimator::FrameRate;
+use crate::animation::Animation;
+use crate::level::ItemKind;
+
+/// Textures, atlases, fonts and animations shared by everything spawned from the level, built
+/// once at startup so a level full of herbs doesn't create an atlas per herb.
+#[derive(Resource)]
+pub struct GameAssets {
+    pub ship: Handle<TextureAtlas>,
+    pub laser: Handle<Image>,
+    pub destructive_beam: Handle<TextureAtlas>,
+    pub light_speed: Handle<TextureAtlas>,
+    pub light_speed_animation: Animation,
+    pub organism_animation: Animation,
+    pub nameplate_font: Handle<Font>,
+    pub title_font: Handle<Font>,
+    pub text_font: Handle<Font>,
+    items: HashMap<ItemKind, Handle<TextureAtlas>>,
+    item_icons: HashMap<ItemKind, Handle<Image>>,
+}
+
+impl FromWorld for GameAssets {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>().clone();
+        let mut texture_atlases = world.resource_mut::<Assets<TextureAtlas>>();
+        let mut atlas = |path: &str, tile_size: Vec2, columns: usize| {
+            texture_atlases.add(TextureAtlas::from_grid(asset_server.load(path), tile_size, columns, 1, None, None))
+        };
+        let items = [
+            (ItemKind::Herbs, atlas("resources.png", Vec2::new(16., 16.), 1)),
+            (ItemKind::Gold, atlas("resources-gold.png", Vec2::new(16., 16.), 1)),
+            (ItemKind::Element251, atlas("resources-element-251.png", Vec2::new(16., 16.), 1)),
+            (ItemKind::Water, atlas("resources-water.png", Vec2::new(16., 16.), 1)),
+            (ItemKind::WeaponArtifact, atlas("artifact.png", Vec2::new(16., 16.), 1)),
+            (ItemKind::ShieldArtifact, atlas("artifact-shield.png", Vec2::new(16., 16.), 1)),
+            (ItemKind::Organism, atlas("organism-sheet.png", Vec2::new(32., 32.), 12)),
+            (ItemKind::YellowOrganism, atlas("organism-yellow-sheet.png", Vec2::new(32., 32.), 12)),
+        ].into_iter().collect();
+        let item_icons = ItemKind::ALL.iter().map(|kind| (*kind, asset_server.load(kind.icon()))).collect();
+        GameAssets {
+            ship: atlas("Bob's Ship-sheet.png", Vec2::new(32., 32.), 2),
+            destructive_beam: atlas("destructive-beam-sheet.png", Vec2::new(128., 48.), 8),
+            light_speed: atlas("light speed.png", Vec2::new(448., 224.), 16),
+            items,
+            item_icons,
+            laser: asset_server.load("Laser Lvl 1.png"),
+            light_speed_animation: Animation(benimator::Animation::from_indices(0..=15, FrameRate::from_fps(10.0))),
+            organism_animation: Animation(benimator::Animation::from_indices(0..=11, FrameRate::from_fps(10.0))),
+            nameplate_font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+            title_font: asset_server.load("fonts/JollyLodger-Regular.ttf"),
+            text_font: asset_server.load("fonts/static/JetBrainsMono-Regular.ttf"),
+        }
+    }
+}
+
+impl GameAssets {
+    pub fn item_atlas(&self, kind: ItemKind) -> Handle<TextureAtlas> {
+        self.items[&kind].clone()
+    }
+
+    /// Every file behind these assets, so the loading screen can wait for them.
+    pub fn files(&self, texture_atlases: &Assets<TextureAtlas>) -> Vec<HandleUntyped> {
+        [&self.ship, &self.destructive_beam, &self.light_speed].into_iter()
+            .chain(self.items.values())
+            .filter_map(|atlas| texture_atlases.get(atlas))
+            .map(|atlas| atlas.texture.clone_untyped())
+            .chain([self.laser.clone_untyped()])
+            .chain(self.item_icons.values().map(|icon| icon.clone_untyped()))
+            .chain([&self.nameplate_font, &self.title_font, &self.text_font].map(|font| font.clone_untyped()))
+            .collect()
+    }
+
+    /// The inventory and crafting icon for an item.
+    pub fn item_icon(&self, kind: ItemKind) -> Handle<Image> {
+        self.item_icons[&kind].clone()
+    }
+}
+
+#[cfg(test)]
+mod game_assets_tests {
+    use bevy::asset::AssetPlugin;
+    use bevy_ecs_ldtk::EntityInstance;
+    use crate::hazards::HazardDisc;
+    use crate::level::{spawn_entity_instances, Item};
+    use super::*;
+
+    #[test]
+    fn it_shares_atlases_between_every_spawned_item() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugin(AssetPlugin::default());
+        app.add_asset::<Image>();
+        app.add_asset::<TextureAtlas>();
+        app.init_resource::<HazardDisc>();
+        app.init_resource::<GameAssets>();
+        app.add_system(spawn_entity_instances);
+        let atlases_before = app.world.resource::<Assets<TextureAtlas>>().len();
+
+        let identifiers = ["Herbs", "Gold", "Element251", "Water", "Organism", "Hostiles", "LightSpeed"];
+        for index in 0..3000 {
+            app.world.spawn((
+                EntityInstance { identifier: identifiers[index % identifiers.len()].to_string(), ..default() },
+                TransformBundle::default(),
+            ));
+        }
+        app.update();
+
+        let mut item_q = app.world.query::<&Handle<TextureAtlas>>();
+        assert_eq!(item_q.iter(&app.world).count(), 3000);
+        assert_eq!(app.world.resource::<Assets<TextureAtlas>>().len(), atlases_before);
+        let mut icon_q = app.world.query::<&Item>();
+        let game_assets = app.world.resource::<GameAssets>();
+        assert!(icon_q.iter(&app.world).all(|item| item.texture == game_assets.item_icon(item.kind)));
+    }
+}
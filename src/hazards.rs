@@ -0,0 +1,297 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy_ecs_ldtk::EntityInstance;
+use bevy_rapier2d::geometry::{ActiveEvents, Collider, Sensor};
+use bevy_rapier2d::pipeline::CollisionEvent;
+use crate::AppState;
+use crate::damage::{DamageSource, DamageTakenEvent};
+use crate::entity_fields::EntityFields;
+use crate::level::Health;
+use crate::ship::Ship;
+use crate::status_effects::{StatusEffect, StatusEffects};
+use crate::ui::InGameUI;
+
+const DISC_TEXTURE_SIZE: u32 = 64;
+const HAZARD_TICK: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum HazardKind {
+    Radiation,
+    Acid,
+    Storm,
+}
+
+impl HazardKind {
+    /// Matches the values of the `HazardKind` enum in the LDtk project.
+    pub fn from_identifier(identifier: &str) -> Option<Self> {
+        match identifier {
+            "Radiation" => Some(HazardKind::Radiation),
+            "Acid" => Some(HazardKind::Acid),
+            "Storm" => Some(HazardKind::Storm),
+            _ => None,
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            HazardKind::Radiation => Color::hex("#F2D16B").unwrap(),
+            HazardKind::Acid => Color::hex("#7ED957").unwrap(),
+            HazardKind::Storm => Color::hex("#8FD3FF").unwrap(),
+        }
+    }
+
+    pub fn damage_source(&self) -> DamageSource {
+        match self {
+            HazardKind::Radiation => DamageSource::Radiation,
+            HazardKind::Acid => DamageSource::Acid,
+            HazardKind::Storm => DamageSource::Storm,
+        }
+    }
+
+    pub fn status_effect(&self, duration: Duration) -> StatusEffect {
+        match self {
+            HazardKind::Radiation => StatusEffect::radiation(0.25, duration),
+            HazardKind::Acid => StatusEffect::poison(0.5, duration),
+            HazardKind::Storm => StatusEffect::slow(0.5, duration),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct HazardZone {
+    pub kind: HazardKind,
+    pub damage_per_second: f32,
+    pub effect_duration: Duration,
+    pub ship_inside: bool,
+    tick: Timer,
+}
+
+impl HazardZone {
+    pub fn new(kind: HazardKind, damage_per_second: f32, effect_duration: Duration) -> Self {
+        HazardZone {
+            kind,
+            damage_per_second,
+            effect_duration,
+            ship_inside: false,
+            tick: Timer::new(HAZARD_TICK, TimerMode::Repeating),
+        }
+    }
+
+    /// Damage dealt each time the zone ticks while the ship is inside.
+    pub fn damage_per_tick(&self) -> f32 {
+        self.damage_per_second * HAZARD_TICK.as_secs_f32()
+    }
+}
+
+/// Soft filled circle used to draw round zones.
+#[derive(Resource)]
+pub struct HazardDisc(pub Handle<Image>);
+
+impl FromWorld for HazardDisc {
+    fn from_world(world: &mut World) -> Self {
+        let mut images = world.resource_mut::<Assets<Image>>();
+        HazardDisc(images.add(disc_image(DISC_TEXTURE_SIZE)))
+    }
+}
+
+fn disc_image(size: u32) -> Image {
+    let radius = size as f32 / 2.;
+    let data = (0..size * size)
+        .flat_map(|index| {
+            let position = Vec2::new((index % size) as f32 + 0.5, (index / size) as f32 + 0.5);
+            let distance = position.distance(Vec2::splat(radius)) / radius;
+            let alpha = if distance > 1. { 0. } else { 0.6 + 0.4 * distance.powi(4) };
+            [255, 255, 255, (alpha * 255.) as u8]
+        })
+        .collect();
+    Image::new(
+        Extent3d { width: size, height: size, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+/// Sensor, visuals and settings for a `HazardZone` entity placed in LDtk.
+pub fn hazard_bundle(instance: &EntityInstance, transform: Transform, disc: &HazardDisc) -> impl Bundle {
+    let fields = EntityFields::new(instance);
+    let kind = fields.enum_value("Kind", HazardKind::from_identifier).unwrap_or(HazardKind::Radiation);
+    let damage = fields.positive_float("Damage").unwrap_or(0.5);
+    let effect_duration = fields.positive_float("Effect_duration")
+        .and_then(|seconds| Duration::try_from_secs_f32(seconds).ok())
+        .unwrap_or(Duration::from_secs(3));
+    let radius = fields.positive_float("Radius");
+    let (collider, size, texture) = match radius {
+        Some(radius) => (Collider::ball(radius), Vec2::splat(radius * 2.), disc.0.clone()),
+        None => {
+            let size = Vec2::new(instance.width as f32, instance.height as f32);
+            (Collider::cuboid(size.x / 2., size.y / 2.), size, Handle::default())
+        }
+    };
+    (
+        HazardZone::new(kind, damage, effect_duration),
+        collider,
+        Sensor,
+        ActiveEvents::COLLISION_EVENTS,
+        SpriteBundle {
+            sprite: Sprite {
+                color: kind.color().with_a(0.35),
+                custom_size: Some(size),
+                ..default()
+            },
+            texture,
+            transform,
+            ..default()
+        },
+    )
+}
+
+pub fn hazard_occupancy(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut zone_q: Query<&mut HazardZone>,
+    mut ship_q: Query<&mut StatusEffects, With<Ship>>,
+) {
+    for collision_event in collision_events.iter() {
+        let (e1, e2, entered) = match collision_event {
+            CollisionEvent::Started(e1, e2, _) => (e1, e2, true),
+            CollisionEvent::Stopped(e1, e2, _) => (e1, e2, false),
+        };
+        for (zone_entity, ship) in [(e1, e2), (e2, e1)] {
+            let Ok(mut zone) = zone_q.get_mut(*zone_entity) else { continue; };
+            let Ok(mut status) = ship_q.get_mut(*ship) else { continue; };
+            zone.ship_inside = entered;
+            zone.tick.reset();
+            if entered {
+                status.apply(zone.kind.status_effect(zone.effect_duration));
+            }
+        }
+    }
+}
+
+pub fn hazard_effects(
+    time: Res<Time>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut ev_damage_taken: EventWriter<DamageTakenEvent>,
+    mut zone_q: Query<&mut HazardZone>,
+    mut ship_q: Query<(&mut Health, &mut StatusEffects), With<Ship>>,
+) {
+    let Ok((mut health, mut status)) = ship_q.get_single_mut() else { return; };
+    for mut zone in zone_q.iter_mut() {
+        if !zone.ship_inside { continue; }
+        zone.tick.tick(time.delta());
+        for _ in 0..zone.tick.times_finished_this_tick() {
+            let damage = zone.damage_per_tick();
+            if damage > 0. {
+                health.current -= damage;
+                ev_damage_taken.send(DamageTakenEvent(damage, zone.kind.damage_source()));
+            }
+            status.apply(zone.kind.status_effect(zone.effect_duration));
+        }
+    }
+    if health.current <= 0. {
+        next_state.set(AppState::GameOver);
+    }
+}
+
+#[derive(Component)]
+pub struct HazardTint;
+
+pub fn setup_hazard_tint(mut commands: Commands) {
+    commands.spawn((InGameUI, HazardTint, NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+            ..default()
+        },
+        background_color: Color::NONE.into(),
+        z_index: ZIndex::Global(-1),
+        ..default()
+    }));
+}
+
+pub fn hazard_tint(
+    zone_q: Query<&HazardZone>,
+    mut tint_q: Query<&mut BackgroundColor, With<HazardTint>>,
+) {
+    let color = zone_q.iter()
+        .find(|zone| zone.ship_inside)
+        .map_or(Color::NONE, |zone| zone.kind.color().with_a(0.15));
+    for mut background in tint_q.iter_mut() {
+        if background.0 != color {
+            background.0 = color;
+        }
+    }
+}
+
+#[cfg(test)]
+mod hazards_tests {
+    use bevy_ecs_ldtk::ldtk::FieldValue;
+    use crate::entity_fields::field_instance;
+    use super::*;
+
+    #[test]
+    fn it_reads_zone_settings_from_ldtk_fields() {
+        let instance = EntityInstance {
+            identifier: "HazardZone".to_string(),
+            width: 64,
+            height: 32,
+            field_instances: vec![
+                field_instance("Kind", FieldValue::Enum(Some("Acid".to_string()))),
+                field_instance("Damage", FieldValue::Float(Some(2.))),
+            ],
+            ..default()
+        };
+        let disc = HazardDisc(Handle::default());
+        let mut world = World::new();
+        let zone = world.spawn(hazard_bundle(&instance, Transform::default(), &disc)).id();
+        let zone = world.entity(zone);
+        let settings = zone.get::<HazardZone>().unwrap();
+        assert_eq!(settings.kind, HazardKind::Acid);
+        assert_eq!(settings.damage_per_tick(), 2.);
+        assert_eq!(settings.effect_duration, Duration::from_secs(3));
+        assert_eq!(zone.get::<Collider>().unwrap().as_cuboid().unwrap().half_extents(), Vec2::new(32., 16.));
+    }
+
+    #[test]
+    fn it_uses_a_circle_when_a_radius_is_set() {
+        let instance = EntityInstance {
+            identifier: "HazardZone".to_string(),
+            field_instances: vec![field_instance("Radius", FieldValue::Float(Some(40.)))],
+            ..default()
+        };
+        let disc = HazardDisc(Handle::default());
+        let mut world = World::new();
+        let zone = world.spawn(hazard_bundle(&instance, Transform::default(), &disc)).id();
+        assert_eq!(world.entity(zone).get::<Collider>().unwrap().as_ball().unwrap().radius(), 40.);
+        assert_eq!(world.entity(zone).get::<HazardZone>().unwrap().kind, HazardKind::Radiation);
+    }
+
+    #[test]
+    fn it_falls_back_to_the_default_duration_for_unusable_values() {
+        for value in [-1., 0., f32::NAN, f32::INFINITY, 1e30] {
+            let instance = EntityInstance {
+                identifier: "HazardZone".to_string(),
+                field_instances: vec![field_instance("Effect_duration", FieldValue::Float(Some(value)))],
+                ..default()
+            };
+            let disc = HazardDisc(Handle::default());
+            let mut world = World::new();
+            let zone = world.spawn(hazard_bundle(&instance, Transform::default(), &disc)).id();
+            assert_eq!(world.entity(zone).get::<HazardZone>().unwrap().effect_duration, Duration::from_secs(3));
+        }
+    }
+
+    #[test]
+    fn it_ignores_damage_that_would_heal_the_ship() {
+        let instance = EntityInstance {
+            identifier: "HazardZone".to_string(),
+            field_instances: vec![field_instance("Damage", FieldValue::Float(Some(-2.)))],
+            ..default()
+        };
+        let disc = HazardDisc(Handle::default());
+        let mut world = World::new();
+        let zone = world.spawn(hazard_bundle(&instance, Transform::default(), &disc)).id();
+        assert_eq!(world.entity(zone).get::<HazardZone>().unwrap().damage_per_tick(), 0.5);
+    }
+}
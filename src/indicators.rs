@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use crate::camera::MainCamera;
+use crate::cursor::get_screen_position;
+use crate::ship::Ship;
+use crate::ui::InGameUI;
+
+const INDICATOR_WIDTH: f32 = 200.;
+const INDICATOR_HEIGHT: f32 = 20.;
+const INDICATOR_SIZE: Size = Size::new(Val::Px(INDICATOR_WIDTH), Val::Px(INDICATOR_HEIGHT));
+const EDGE_PADDING: f32 = 8.;
+const PIXELS_PER_METER: f32 = 16.;
+const ARROWS: [&str; 8] = ["→", "↗", "↑", "↖", "←", "↙", "↓", "↘"];
+
+#[derive(Component)]
+pub struct IndicatorTarget {
+    pub label: &'static str,
+    pub color: Color,
+    pub discovered: bool,
+    indicator: Option<Entity>,
+}
+
+impl IndicatorTarget {
+    pub fn new(label: &'static str, color: Color) -> Self {
+        IndicatorTarget { label, color, discovered: false, indicator: None }
+    }
+}
+
+#[derive(Component)]
+pub struct OffscreenIndicator(pub Entity);
+
+/// Where an indicator sits on the screen edge for a target at `screen_pos`, and the direction
+/// to it in radians. `None` while the target is comfortably on screen.
+pub fn edge_position(screen_pos: Vec2, window_size: Vec2, margin: Vec2) -> Option<(Vec2, f32)> {
+    let half_size = window_size / 2.;
+    let reach = (half_size - margin).max(Vec2::ZERO);
+    let offset = screen_pos - half_size;
+    if offset.x.abs() <= reach.x && offset.y.abs() <= reach.y { return None; }
+    let scale = (reach.x / offset.x.abs()).min(reach.y / offset.y.abs());
+    Some((half_size + offset * scale, offset.y.atan2(offset.x)))
+}
+
+pub fn direction_arrow(angle: f32) -> &'static str {
+    let octant = (angle / std::f32::consts::FRAC_PI_4).round().rem_euclid(8.) as usize;
+    ARROWS[octant % 8]
+}
+
+pub fn discover_on_screen_targets(
+    windows_q: Query<&Window, With<PrimaryWindow>>,
+    camera_q: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut target_q: Query<(&GlobalTransform, &mut IndicatorTarget)>,
+) {
+    let Ok(wnd) = windows_q.get_single() else { return; };
+    let Ok((camera, camera_transform)) = camera_q.get_single() else { return; };
+    let window_size = Vec2::new(wnd.width(), wnd.height());
+    for (target_transform, mut target) in target_q.iter_mut() {
+        if target.discovered { continue; }
+        let screen_pos = get_screen_position(camera, camera_transform, wnd, target_transform.translation().truncate());
+        if edge_position(screen_pos, window_size, Vec2::ZERO).is_none() {
+            target.discovered = true;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn offscreen_indicators(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    windows_q: Query<&Window, With<PrimaryWindow>>,
+    camera_q: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    ship_q: Query<&GlobalTransform, With<Ship>>,
+    mut target_q: Query<(Entity, &GlobalTransform, &mut IndicatorTarget)>,
+    mut indicator_q: Query<(Entity, &OffscreenIndicator, &mut Style, &mut Visibility, &Children)>,
+    mut text_q: Query<&mut Text>,
+) {
+    let Ok(wnd) = windows_q.get_single() else { return; };
+    let Ok((camera, camera_transform)) = camera_q.get_single() else { return; };
+    let Ok(ship_transform) = ship_q.get_single() else { return; };
+    let window_size = Vec2::new(wnd.width(), wnd.height());
+    for (target_entity, _, mut target) in target_q.iter_mut() {
+        if !target.discovered || target.indicator.is_some() { continue; }
+        let indicator = commands.spawn((InGameUI, OffscreenIndicator(target_entity), NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: INDICATOR_SIZE,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        })).with_children(|parent| {
+            parent.spawn(TextBundle::from_section("", TextStyle {
+                font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+                font_size: 14.,
+                color: target.color,
+            }));
+        }).id();
+        target.indicator = Some(indicator);
+    }
+    let half_indicator = Vec2::new(INDICATOR_WIDTH, INDICATOR_HEIGHT) / 2.;
+    for (indicator_entity, indicator, mut style, mut visibility, children) in indicator_q.iter_mut() {
+        let Ok((_, target_transform, target)) = target_q.get(indicator.0) else {
+            commands.entity(indicator_entity).despawn_recursive();
+            continue;
+        };
+        let target_pos = target_transform.translation().truncate();
+        let screen_pos = get_screen_position(camera, camera_transform, wnd, target_pos);
+        let Some((edge, angle)) = edge_position(screen_pos, window_size, half_indicator + EDGE_PADDING) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        let meters = ship_transform.translation().truncate().distance(target_pos) / PIXELS_PER_METER;
+        for child in children.iter() {
+            if let Ok(mut text) = text_q.get_mut(*child) {
+                text.sections[0].value = format!("{} {} {:.0}m", direction_arrow(angle), target.label, meters);
+            }
+        }
+        style.position = UiRect {
+            left: Val::Px(edge.x - half_indicator.x),
+            top: Val::Px(window_size.y - edge.y - half_indicator.y),
+            ..default()
+        };
+        *visibility = Visibility::Inherited;
+    }
+}
+
+#[cfg(test)]
+mod indicators_tests {
+    use super::*;
+
+    #[test]
+    fn it_hides_indicators_for_targets_on_screen() {
+        assert_eq!(edge_position(Vec2::new(400., 300.), Vec2::new(800., 600.), Vec2::splat(20.)), None);
+    }
+
+    #[test]
+    fn it_clamps_off_screen_targets_to_the_edge() {
+        let window = Vec2::new(800., 600.);
+        let (edge, angle) = edge_position(Vec2::new(1600., 300.), window, Vec2::splat(20.)).unwrap();
+        assert_eq!(edge, Vec2::new(780., 300.));
+        assert_eq!(angle, 0.);
+        let (edge, _) = edge_position(Vec2::new(400., -900.), window, Vec2::splat(20.)).unwrap();
+        assert_eq!(edge, Vec2::new(400., 20.));
+    }
+
+    #[test]
+    fn it_points_arrows_toward_the_target() {
+        assert_eq!(direction_arrow(0.), "→");
+        assert_eq!(direction_arrow(std::f32::consts::FRAC_PI_2), "↑");
+        assert_eq!(direction_arrow(-std::f32::consts::FRAC_PI_4), "↘");
+        assert_eq!(direction_arrow(std::f32::consts::PI), "←");
+        assert_eq!(direction_arrow(-std::f32::consts::PI), "←");
+    }
+}
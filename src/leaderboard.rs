@@ -0,0 +1,183 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::level::ItemKind;
+use crate::stats::RunStats;
+use crate::storage;
+
+const LEADERBOARD_KEY: &str = "leaderboard";
+pub const LEADERBOARD_SIZE: usize = 10;
+const POINTS_PER_SECOND: u32 = 2;
+
+/// Points an item is worth when the run ends, roughly by how hard it is to get.
+pub fn item_points(kind: ItemKind) -> u32 {
+    match kind {
+        ItemKind::Herbs | ItemKind::Water => 10,
+        ItemKind::Gold => 50,
+        ItemKind::Organism => 75,
+        ItemKind::YellowOrganism => 120,
+        ItemKind::Element251 => 150,
+        ItemKind::WeaponArtifact | ItemKind::ShieldArtifact => 250,
+    }
+}
+
+pub fn score(stats: &RunStats) -> u32 {
+    let items: u32 = stats.items_collected.iter()
+        .map(|(kind, count)| item_points(*kind) * count)
+        .sum();
+    items + stats.time_survived.as_secs() as u32 * POINTS_PER_SECOND
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct RunRecord {
+    /// Seconds since the Unix epoch when the run ended.
+    pub ended_at: u64,
+    pub score: u32,
+    pub level: usize,
+    pub seed: u64,
+}
+
+/// Best runs on this machine, highest score first.
+#[derive(Resource, Serialize, Deserialize, Default, PartialEq, Debug)]
+pub struct Leaderboard {
+    pub runs: Vec<RunRecord>,
+    /// Position of the run that just ended, if it made the table.
+    #[serde(skip)]
+    pub last_rank: Option<usize>,
+}
+
+impl Leaderboard {
+    pub fn load() -> Self {
+        let Some(text) = storage::read(LEADERBOARD_KEY) else { return Leaderboard::default(); };
+        ron::from_str(&text).unwrap_or_else(|error| {
+            warn!("Ignoring malformed leaderboard: {}", error);
+            Leaderboard::default()
+        })
+    }
+
+    pub fn save(&self) {
+        match ron::to_string(self) {
+            Ok(text) => storage::write(LEADERBOARD_KEY, &text),
+            Err(error) => warn!("Could not serialize leaderboard: {}", error),
+        }
+    }
+
+    /// Inserts the run after any with an equal or higher score and drops whatever falls off the
+    /// bottom. Returns the run's position if it stayed on the table.
+    pub fn submit(&mut self, record: RunRecord) -> Option<usize> {
+        let rank = self.runs.iter().position(|run| run.score < record.score).unwrap_or(self.runs.len());
+        self.runs.insert(rank, record);
+        self.runs.truncate(LEADERBOARD_SIZE);
+        self.last_rank = (rank < LEADERBOARD_SIZE).then_some(rank);
+        self.last_rank
+    }
+}
+
+/// `YYYY-MM-DD` for a Unix timestamp, without pulling in a date crate.
+pub fn format_date(unix_seconds: u64) -> String {
+    // Civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+    let days = (unix_seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+pub fn record_run(stats: Res<RunStats>, mut leaderboard: ResMut<Leaderboard>) {
+    leaderboard.submit(RunRecord {
+        ended_at: storage::unix_time(),
+        score: score(&stats),
+        level: stats.level,
+        seed: stats.seed,
+    });
+    leaderboard.save();
+}
+
+/// Adds the table of best runs under `parent`, highlighting the run that just ended.
+pub fn spawn_leaderboard(parent: &mut ChildBuilder, asset_server: &AssetServer, leaderboard: &Leaderboard, highlight_last: bool) {
+    let font = asset_server.load("fonts/static/JetBrainsMono-Light.ttf");
+    parent.spawn(NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    }).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            "Best Runs",
+            TextStyle { font: font.clone(), font_size: 24., color: Color::hex("#FFF").unwrap() },
+        ));
+        if leaderboard.runs.is_empty() {
+            parent.spawn(TextBundle::from_section(
+                "No runs yet",
+                TextStyle { font: font.clone(), font_size: 18., color: Color::GRAY },
+            ));
+        }
+        for (rank, run) in leaderboard.runs.iter().enumerate() {
+            let color = if highlight_last && leaderboard.last_rank == Some(rank) {
+                Color::hex("#9FF4FF").unwrap()
+            } else {
+                Color::hex("#FFF").unwrap()
+            };
+            parent.spawn(TextBundle::from_section(
+                format!("{:>2}. {:>6}  {}  Level {}  Seed {:08x}", rank + 1, run.score, format_date(run.ended_at), run.level, run.seed),
+                TextStyle { font: font.clone(), font_size: 18., color },
+            ));
+        }
+    });
+}
+
+#[cfg(test)]
+mod leaderboard_tests {
+    use std::time::Duration;
+    use super::*;
+
+    fn run(score: u32) -> RunRecord {
+        RunRecord { ended_at: 0, score, level: 0, seed: 0 }
+    }
+
+    #[test]
+    fn it_scores_items_and_survival_time() {
+        let mut stats = RunStats::new(0, 0);
+        stats.time_survived = Duration::from_secs(30);
+        stats.record_item(ItemKind::Gold);
+        stats.record_item(ItemKind::Herbs);
+        assert_eq!(score(&stats), 50 + 10 + 60);
+    }
+
+    #[test]
+    fn it_keeps_only_the_best_runs_in_order() {
+        let mut leaderboard = Leaderboard::default();
+        for score in 0..LEADERBOARD_SIZE as u32 {
+            leaderboard.submit(run(score * 10));
+        }
+        assert_eq!(leaderboard.submit(run(45)), Some(5));
+        assert_eq!(leaderboard.runs.len(), LEADERBOARD_SIZE);
+        assert_eq!(leaderboard.runs.first().unwrap().score, 90);
+        assert_eq!(leaderboard.runs.last().unwrap().score, 10);
+        assert_eq!(leaderboard.submit(run(5)), None);
+        assert_eq!(leaderboard.last_rank, None);
+    }
+
+    #[test]
+    fn it_round_trips_through_ron() {
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.submit(RunRecord { ended_at: 1_700_000_000, score: 320, level: 0, seed: 42 });
+        let text = ron::to_string(&leaderboard).unwrap();
+        let loaded: Leaderboard = ron::from_str(&text).unwrap();
+        assert_eq!(loaded.runs, leaderboard.runs);
+        assert_eq!(loaded.last_rank, None);
+    }
+
+    #[test]
+    fn it_formats_dates() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_700_000_000), "2023-11-14");
+    }
+}
@@ -1,24 +1,114 @@
 use std::slice::Iter;
+use std::time::Duration;
 use bevy::prelude::*;
-use bevy::asset::{Assets, AssetServer};
 use bevy::math::Vec2;
 use bevy::hierarchy::BuildChildren;
 use bevy_ecs_ldtk::EntityInstance;
-use bevy_rapier2d::dynamics::{GravityScale, RigidBody, Velocity};
-use bevy_rapier2d::geometry::{ActiveEvents, Collider, Sensor};
-use benimator::FrameRate;
+use bevy_rapier2d::dynamics::{Damping, ExternalForce, GravityScale, RigidBody, Velocity};
+use bevy_rapier2d::geometry::{ActiveEvents, Collider, ColliderMassProperties, Sensor};
 
-use crate::beams::InteractLightBeam;
-use crate::animation::{Animation, AnimationState};
+use crate::beams::{BeamEnergy, BeamLevel, InteractLightBeam, TractorResistance};
+use crate::animation::AnimationState;
+use crate::boost::Boost;
+use crate::damage::DamageSource;
+use crate::entity_fields::EntityFields;
+use crate::game_assets::GameAssets;
+use crate::hazards::{hazard_bundle, HazardDisc};
+use crate::indicators::IndicatorTarget;
+use crate::life_support::LifeSupport;
 use crate::movement::Speed;
+use crate::positional_audio::SoundEmitter;
+use crate::scanner::Scanner;
 use crate::ship::Ship;
+use crate::status_effects::{ContactEffect, StatusEffect, StatusEffects};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ItemKind {
+    Herbs,
+    Gold,
+    Element251,
+    Water,
+    WeaponArtifact,
+    ShieldArtifact,
+    Organism,
+    YellowOrganism,
+}
+
+impl ItemKind {
+    pub const ALL: [ItemKind; 8] = [
+        ItemKind::Herbs,
+        ItemKind::Gold,
+        ItemKind::Element251,
+        ItemKind::Water,
+        ItemKind::WeaponArtifact,
+        ItemKind::ShieldArtifact,
+        ItemKind::Organism,
+        ItemKind::YellowOrganism,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ItemKind::Herbs => "Herbs",
+            ItemKind::Gold => "Gold",
+            ItemKind::Element251 => "Element251",
+            ItemKind::Water => "Water",
+            ItemKind::WeaponArtifact => "Weapon Artifact",
+            ItemKind::ShieldArtifact => "Shield Artifact",
+            ItemKind::Organism => "Organism",
+            ItemKind::YellowOrganism => "Hostile Organism",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ItemKind::Herbs => "Herbs:\nGain Life Support.\nSide Effects: Locals will come after you.",
+            ItemKind::Gold => "Gold:\nGreat, I definitely need this.\n.",
+            ItemKind::Element251 => "Element251:\nRare element that unlocks advanced technology.\nSide Effects: ???.",
+            ItemKind::Water => "Water:\nEssential for survival. Gain a little Life Support.\nSide Effects: Taking water will anger locals.",
+            ItemKind::WeaponArtifact => "Weapon Artifact:\nRadioactive weapon, capable of destruction.\n\
+                Side Effects:\nConsume 2 gold, 1 element251, 3 water, 5 organisms to activate.",
+            ItemKind::ShieldArtifact => "Shield Artifact:\nReflective capabilities.\n\
+                Side Effects:\nConsumes 15 water, 1 element251, 3 herbs, 1 organisms to activate.",
+            ItemKind::Organism => "Organism:\nCan be genetically modified to work for you.\nSide Effect: Could turn against you.",
+            ItemKind::YellowOrganism => "Hostile Organism:\nAlready turned against you. Too aggressive to modify.\nSide Effect: Bites.",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            ItemKind::Herbs => "resources.png",
+            ItemKind::Gold => "resources-gold.png",
+            ItemKind::Element251 => "resources-element-251.png",
+            ItemKind::Water => "resources-water.png",
+            ItemKind::WeaponArtifact => "artifact.png",
+            ItemKind::ShieldArtifact => "artifact-shield.png",
+            ItemKind::Organism => "organism.png",
+            ItemKind::YellowOrganism => "organism-yellow.png",
+        }
+    }
+}
 
 #[derive(Component, Clone)]
 pub struct Item {
+    pub kind: ItemKind,
     pub description: String,
     pub texture: Handle<Image>,
 }
 
+impl Item {
+    pub fn from_kind(kind: ItemKind, game_assets: &GameAssets) -> Self {
+        Item {
+            kind,
+            description: kind.description().to_string(),
+            texture: game_assets.item_icon(kind),
+        }
+    }
+}
+
+/// How many units of an item the ship gets when it beams this one up, e.g. a bigger herb patch.
+#[derive(Component)]
+pub struct Quantity(pub u32);
+
 #[derive(Component)]
 pub struct ResourceNameplate;
 
@@ -50,14 +140,46 @@ impl Inventory {
             items: vec![],
         }
     }
-    pub fn add(&mut self, item: &Item) {
-        if self.items.len() >= self.capacity as usize { return; }
+    /// Returns false, leaving the inventory untouched, when it is already full.
+    pub fn add(&mut self, item: &Item) -> bool {
+        if !self.has_room(1) { return false; }
         self.items.push(item.clone());
+        true
+    }
+
+    pub fn has_room(&self, count: u32) -> bool {
+        self.items.len() + count as usize <= self.capacity as usize
     }
 
     pub fn iter(&self) -> Iter<'_, Item> {
         self.items.iter()
     }
+
+    pub fn count(&self, kind: ItemKind) -> usize {
+        self.items.iter().filter(|item| item.kind == kind).count()
+    }
+
+    pub fn can_afford(&self, cost: &[(ItemKind, usize)]) -> bool {
+        cost.iter().all(|(kind, count)| self.count(*kind) >= *count)
+    }
+
+    /// Removes every item in `cost`, or nothing at all if any of them is missing.
+    pub fn spend(&mut self, cost: &[(ItemKind, usize)]) -> bool {
+        if !self.can_afford(cost) { return false; }
+        for (kind, count) in cost {
+            for _ in 0..*count {
+                if let Some(index) = self.items.iter().position(|item| item.kind == *kind) {
+                    self.items.remove(index);
+                }
+            }
+        }
+        true
+    }
+
+    pub fn remove_at(&mut self, index: usize) -> Option<Item> {
+        if index >= self.items.len() { return None; }
+        Some(self.items.remove(index))
+    }
 }
 
 #[derive(Component, Clone)]
@@ -90,29 +212,27 @@ pub struct AggroRange;
 #[derive(Component)]
 pub struct DestructiveLightBeam;
 
+#[allow(clippy::type_complexity)]
 pub fn spawn_entity_instances(
     mut commands: Commands,
     player_q: Query<(Entity, &EntityInstance, &Transform, &GlobalTransform), (Added<EntityInstance>, Without<Ship>)>,
     mut bob_ship_q: Query<&mut Transform, With<Ship>>,
-    asset_server: Res<AssetServer>,
-    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    game_assets: Res<GameAssets>,
+    hazard_disc: Res<HazardDisc>,
 ) {
     for
     (entity, instance, p_transform, global_transform)
     in player_q.iter() {
+        let fields = EntityFields::new(instance);
         match instance.identifier.as_ref() {
             "Player" => {
                 if bob_ship_q.is_empty() {
-                    let texture_handle = asset_server.load("Bob's Ship-sheet.png");
-                    let texture_atlas =
-                        TextureAtlas::from_grid(texture_handle, Vec2::new(32.0, 32.0), 2, 1, None, None);
-                    let texture_atlas_handle = texture_atlases.add(texture_atlas);
                     let bob_bundle = (
                         Ship,
-                        Health::new(10.),
-                        Inventory::new(50),
+                        Health::new(fields.positive_float("Health").unwrap_or(10.)),
+                        Inventory::new(fields.count("Inventory_capacity").unwrap_or(50)),
                         SpriteSheetBundle {
-                            texture_atlas: texture_atlas_handle,
+                            texture_atlas: game_assets.ship.clone(),
                             transform: *p_transform,
                             ..default()
                         },
@@ -120,8 +240,13 @@ pub fn spawn_entity_instances(
                         RigidBody::Dynamic,
                         GravityScale(0.),
                         Velocity::zero(),
-                        Speed(90.),
-                        AnimationState::default()
+                        Speed(fields.positive_float("Speed").unwrap_or(90.)),
+                        Boost::default(),
+                        Scanner::default(),
+                        BeamEnergy::default(),
+                        BeamLevel::default(),
+                        AnimationState::default(),
+                        (LifeSupport::default(), StatusEffects::default()),
                     );
                     commands.entity(entity).insert(bob_bundle).with_children(|parent| {
                         let mut light_beam_translation = Transform::from(*global_transform);
@@ -129,7 +254,7 @@ pub fn spawn_entity_instances(
                         light_beam_translation.translation.z += 1.;
                         parent.spawn((InteractLightBeam,
                                       SpriteBundle {
-                                          texture: asset_server.load("Laser Lvl 1.png"),
+                                          texture: game_assets.laser.clone(),
                                           transform: light_beam_translation,
                                           visibility: Visibility::Hidden,
                                           ..default()
@@ -137,13 +262,9 @@ pub fn spawn_entity_instances(
                         let mut destructive_beam_translation = Transform::from(*global_transform);
                         destructive_beam_translation.translation.x += 8. * 10.;
                         destructive_beam_translation.translation.z += 1.;
-                        let texture_handle = asset_server.load("destructive-beam-sheet.png");
-                        let texture_atlas =
-                            TextureAtlas::from_grid(texture_handle, Vec2::new(128., 48.0), 8, 1, None, None);
-                        let texture_atlas_handle = texture_atlases.add(texture_atlas);
                         parent.spawn((DestructiveLightBeam,
                                       SpriteSheetBundle {
-                                          texture_atlas: texture_atlas_handle,
+                                          texture_atlas: game_assets.destructive_beam.clone(),
                                           transform: destructive_beam_translation,
                                           visibility: Visibility::Hidden,
                                           ..default()
@@ -156,41 +277,29 @@ pub fn spawn_entity_instances(
                 }
             }
             "LightSpeed" => {
-                let texture_handle = asset_server.load("light speed.png");
-                let texture_atlas =
-                    TextureAtlas::from_grid(texture_handle, Vec2::new(448., 224.), 16, 1, None, None);
-                let texture_atlas_handle = texture_atlases.add(texture_atlas);
-                let animation = Animation(benimator::Animation::from_indices(
-                    0..=15,
-                    FrameRate::from_fps(10.0),
-                ));
                 commands.entity(entity).insert((
                     LightSpeed,
                     SpriteSheetBundle {
-                        texture_atlas: texture_atlas_handle,
+                        texture_atlas: game_assets.light_speed.clone(),
                         transform: *p_transform,
                         visibility: Visibility::Hidden,
                         ..default()
                     },
-                    animation,
+                    game_assets.light_speed_animation.clone(),
                     AnimationState::default(),
                 ));
             }
             "Herbs" => {
-                let texture_handle = asset_server.load("resources.png");
-                let texture_atlas =
-                    TextureAtlas::from_grid(texture_handle.clone(), Vec2::new(16., 16.), 1, 1, None, None);
-                let texture_atlas_handle = texture_atlases.add(texture_atlas);
+                let quantity = fields.count("Quantity").unwrap_or(1);
                 commands.entity(entity).insert((
                     Herbs,
-                    Item {
-                        description: "Herbs:\nGain Life Support.\nSide Effects: Locals will come after you.".to_string(),
-                        texture: asset_server.load("resources.png"),
-                    },
+                    Item::from_kind(ItemKind::Herbs, &game_assets),
+                    Quantity(quantity),
                     Collider::ball(8.),
                     Sensor,
+                    tractor_body(1.),
                     SpriteSheetBundle {
-                        texture_atlas: texture_atlas_handle,
+                        texture_atlas: game_assets.item_atlas(ItemKind::Herbs),
                         transform: *p_transform,
                         ..default()
                     },
@@ -198,8 +307,8 @@ pub fn spawn_entity_instances(
                     parent.spawn((
                         ResourceNameplate,
                         Text2dBundle {
-                            text: Text::from_section("Herbs", TextStyle {
-                                font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+                            text: Text::from_section(nameplate_label("Herbs", quantity), TextStyle {
+                                font: game_assets.nameplate_font.clone(),
                                 font_size: 16.,
                                 ..default()
                             }),
@@ -211,20 +320,16 @@ pub fn spawn_entity_instances(
                 });
             }
             "Gold" => {
-                let texture_handle = asset_server.load("resources-gold.png");
-                let texture_atlas =
-                    TextureAtlas::from_grid(texture_handle.clone(), Vec2::new(16., 16.), 1, 1, None, None);
-                let texture_atlas_handle = texture_atlases.add(texture_atlas);
+                let quantity = fields.count("Quantity").unwrap_or(1);
                 commands.entity(entity).insert((
                     Gold,
-                    Item {
-                        description: "Gold:\nGreat, I definitely need this.\n.".to_string(),
-                        texture: asset_server.load("resources-gold.png"),
-                    },
+                    Item::from_kind(ItemKind::Gold, &game_assets),
+                    Quantity(quantity),
                     Collider::ball(8.),
                     Sensor,
+                    tractor_body(1.),
                     SpriteSheetBundle {
-                        texture_atlas: texture_atlas_handle,
+                        texture_atlas: game_assets.item_atlas(ItemKind::Gold),
                         transform: *p_transform,
                         ..default()
                     },
@@ -232,8 +337,8 @@ pub fn spawn_entity_instances(
                     parent.spawn((
                         ResourceNameplate,
                         Text2dBundle {
-                            text: Text::from_section("Gold", TextStyle {
-                                font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+                            text: Text::from_section(nameplate_label("Gold", quantity), TextStyle {
+                                font: game_assets.nameplate_font.clone(),
                                 font_size: 16.,
                                 ..default()
                             }),
@@ -245,20 +350,16 @@ pub fn spawn_entity_instances(
                 });
             }
             "Element251" => {
-                let texture_handle = asset_server.load("resources-element-251.png");
-                let texture_atlas =
-                    TextureAtlas::from_grid(texture_handle.clone(), Vec2::new(16., 16.), 1, 1, None, None);
-                let texture_atlas_handle = texture_atlases.add(texture_atlas);
+                let quantity = fields.count("Quantity").unwrap_or(1);
                 commands.entity(entity).insert((
                     Element251,
-                    Item {
-                        description: "Element251:\nRare element that unlocks advanced technology.\nSide Effects: ???.".to_string(),
-                        texture: asset_server.load("resources-element-251.png"),
-                    },
+                    Item::from_kind(ItemKind::Element251, &game_assets),
+                    Quantity(quantity),
                     Collider::ball(8.),
                     Sensor,
+                    tractor_body(1.),
                     SpriteSheetBundle {
-                        texture_atlas: texture_atlas_handle,
+                        texture_atlas: game_assets.item_atlas(ItemKind::Element251),
                         transform: *p_transform,
                         ..default()
                     },
@@ -266,8 +367,8 @@ pub fn spawn_entity_instances(
                     parent.spawn((
                         ResourceNameplate,
                         Text2dBundle {
-                            text: Text::from_section("Element251", TextStyle {
-                                font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+                            text: Text::from_section(nameplate_label("Element251", quantity), TextStyle {
+                                font: game_assets.nameplate_font.clone(),
                                 font_size: 16.,
                                 ..default()
                             }),
@@ -279,20 +380,16 @@ pub fn spawn_entity_instances(
                 });
             }
             "Water" => {
-                let texture_handle = asset_server.load("resources-water.png");
-                let texture_atlas =
-                    TextureAtlas::from_grid(texture_handle.clone(), Vec2::new(16., 16.), 1, 1, None, None);
-                let texture_atlas_handle = texture_atlases.add(texture_atlas);
+                let quantity = fields.count("Quantity").unwrap_or(1);
                 commands.entity(entity).insert((
                     Water,
-                    Item {
-                        description: "Water:\nEssential for survival.\nSide Effects: Taking water will anger locals.".to_string(),
-                        texture: asset_server.load("resources-water.png"),
-                    },
+                    Item::from_kind(ItemKind::Water, &game_assets),
+                    Quantity(quantity),
                     Collider::ball(8.),
                     Sensor,
+                    tractor_body(1.),
                     SpriteSheetBundle {
-                        texture_atlas: texture_atlas_handle,
+                        texture_atlas: game_assets.item_atlas(ItemKind::Water),
                         transform: *p_transform,
                         ..default()
                     },
@@ -300,8 +397,8 @@ pub fn spawn_entity_instances(
                     parent.spawn((
                         ResourceNameplate,
                         Text2dBundle {
-                            text: Text::from_section("Water", TextStyle {
-                                font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+                            text: Text::from_section(nameplate_label("Water", quantity), TextStyle {
+                                font: game_assets.nameplate_font.clone(),
                                 font_size: 16.,
                                 ..default()
                             }),
@@ -313,21 +410,16 @@ pub fn spawn_entity_instances(
                 });
             }
             "WeaponArtifact" => {
-                let texture_handle = asset_server.load("artifact.png");
-                let texture_atlas =
-                    TextureAtlas::from_grid(texture_handle.clone(), Vec2::new(16., 16.), 1, 1, None, None);
-                let texture_atlas_handle = texture_atlases.add(texture_atlas);
                 commands.entity(entity).insert((
                     WeaponArtifact,
-                    Item {
-                        description: "Weapon Artifact:\nRadioactive weapon, capable of destruction.\n\
-                        Side Effects:\nConsume 2 gold, 1 element251, 3 water, 5 organisms to activate.".to_string(),
-                        texture: asset_server.load("artifact.png"),
-                    },
+                    Item::from_kind(ItemKind::WeaponArtifact, &game_assets),
+                    SoundEmitter::artifact(),
+                    IndicatorTarget::new("Weapon Artifact", Color::hex("#FF8A5B").unwrap()),
                     Collider::ball(8.),
                     Sensor,
+                    tractor_body(2.),
                     SpriteSheetBundle {
-                        texture_atlas: texture_atlas_handle,
+                        texture_atlas: game_assets.item_atlas(ItemKind::WeaponArtifact),
                         transform: *p_transform,
                         ..default()
                     },
@@ -336,7 +428,7 @@ pub fn spawn_entity_instances(
                         ResourceNameplate,
                         Text2dBundle {
                             text: Text::from_section("Weapon Artifact", TextStyle {
-                                font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+                                font: game_assets.nameplate_font.clone(),
                                 font_size: 16.,
                                 ..default()
                             }),
@@ -348,21 +440,16 @@ pub fn spawn_entity_instances(
                 });
             }
             "ShieldArtifact" => {
-                let texture_handle = asset_server.load("artifact-shield.png");
-                let texture_atlas =
-                    TextureAtlas::from_grid(texture_handle.clone(), Vec2::new(16., 16.), 1, 1, None, None);
-                let texture_atlas_handle = texture_atlases.add(texture_atlas);
                 commands.entity(entity).insert((
                     ShieldArtifact,
-                    Item {
-                        description: "Shield Artifact:\nReflective capabilities.\n\
-                        Side Effects:\nConsumes 15 water, 1 element251, 3 herbs, 1 organisms to activate.".to_string(),
-                        texture: asset_server.load("artifact-shield.png"),
-                    },
+                    Item::from_kind(ItemKind::ShieldArtifact, &game_assets),
+                    SoundEmitter::artifact(),
+                    IndicatorTarget::new("Shield Artifact", Color::hex("#8FD3FF").unwrap()),
                     Collider::ball(8.),
                     Sensor,
+                    tractor_body(2.),
                     SpriteSheetBundle {
-                        texture_atlas: texture_atlas_handle,
+                        texture_atlas: game_assets.item_atlas(ItemKind::ShieldArtifact),
                         transform: *p_transform,
                         ..default()
                     },
@@ -371,7 +458,7 @@ pub fn spawn_entity_instances(
                         ResourceNameplate,
                         Text2dBundle {
                             text: Text::from_section("Shield Artifact", TextStyle {
-                                font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+                                font: game_assets.nameplate_font.clone(),
                                 font_size: 16.,
                                 ..default()
                             }),
@@ -383,32 +470,28 @@ pub fn spawn_entity_instances(
                 });
             }
             "Organism" => {
-                let texture_handle = asset_server.load("organism-sheet.png");
-                let texture_atlas =
-                    TextureAtlas::from_grid(texture_handle.clone(), Vec2::new(32., 32.), 12, 1, None, None);
-                let texture_atlas_handle = texture_atlases.add(texture_atlas);
-                let animation = Animation(benimator::Animation::from_indices(
-                    0..=11,
-                    FrameRate::from_fps(10.0),
-                ));
+                let aggro_radius = fields.positive_float("Aggro_radius").unwrap_or(96.);
                 commands.entity(entity).insert((
-                    Item {
-                        description: "Organism:\nCan be genetically modified to work for you.\nSide Effect: Could turn against you.".to_string(),
-                        texture: asset_server.load("organism.png"),
-                    },
+                    Item::from_kind(ItemKind::Organism, &game_assets),
                     Organism,
+                    SoundEmitter::organism(chirp_interval(entity)),
                     Collider::ball(14.),
                     Sensor,
-                    animation,
+                    (ExternalForce::default(), tractor_damping(), ColliderMassProperties::Mass(3.), TractorResistance(250.)),
+                    game_assets.organism_animation.clone(),
                     RigidBody::Dynamic,
                     GravityScale(0.),
                     Velocity::zero(),
-                    Speed(80.),
+                    Speed(fields.positive_float("Speed").unwrap_or(80.)),
                     AnimationState::default(),
                     ActiveEvents::COLLISION_EVENTS,
-                    DamageCollider(1.),
+                    (
+                        DamageCollider(fields.positive_float("Damage").unwrap_or(1.), DamageSource::Organism),
+                        StatusEffects::default(),
+                        ContactEffect(StatusEffect::poison(0.5, Duration::from_secs(4))),
+                    ),
                     SpriteSheetBundle {
-                        texture_atlas: texture_atlas_handle,
+                        texture_atlas: game_assets.item_atlas(ItemKind::Organism),
                         transform: *p_transform,
                         ..default()
                     },
@@ -417,7 +500,7 @@ pub fn spawn_entity_instances(
                         ResourceNameplate,
                         Text2dBundle {
                             text: Text::from_section("Life Form", TextStyle {
-                                font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+                                font: game_assets.nameplate_font.clone(),
                                 font_size: 16.,
                                 ..default()
                             }),
@@ -427,7 +510,8 @@ pub fn spawn_entity_instances(
                         },
                     ));
                     parent.spawn((
-                        Collider::ball(96.),
+                        Collider::ball(aggro_radius),
+                        ColliderMassProperties::Density(0.),
                         ActiveEvents::COLLISION_EVENTS,
                         Sensor,
                         AggroRange
@@ -435,32 +519,28 @@ pub fn spawn_entity_instances(
                 });
             }
             "Hostiles" => {
-                let texture_handle = asset_server.load("organism-yellow-sheet.png");
-                let texture_atlas =
-                    TextureAtlas::from_grid(texture_handle.clone(), Vec2::new(32., 32.), 12, 1, None, None);
-                let texture_atlas_handle = texture_atlases.add(texture_atlas);
-                let animation = Animation(benimator::Animation::from_indices(
-                    0..=11,
-                    FrameRate::from_fps(10.0),
-                ));
+                let aggro_radius = fields.positive_float("Aggro_radius").unwrap_or(106.);
                 commands.entity(entity).insert((
-                    Item {
-                        description: "Organism:\nCan be genetically modified to work for you.\nSide Effect: Could turn against you.".to_string(),
-                        texture: asset_server.load("organism-yellow.png"),
-                    },
+                    Item::from_kind(ItemKind::YellowOrganism, &game_assets),
                     YellowOrganism,
+                    SoundEmitter::organism(chirp_interval(entity)),
                     Collider::ball(14.),
                     Sensor,
-                    animation,
+                    (ExternalForce::default(), tractor_damping(), ColliderMassProperties::Mass(4.), TractorResistance(450.)),
+                    game_assets.organism_animation.clone(),
                     AnimationState::default(),
                     RigidBody::Dynamic,
                     GravityScale(0.),
                     Velocity::zero(),
-                    Speed(80.),
+                    Speed(fields.positive_float("Speed").unwrap_or(80.)),
                     ActiveEvents::COLLISION_EVENTS,
-                    DamageCollider(2.),
+                    (
+                        DamageCollider(fields.positive_float("Damage").unwrap_or(2.), DamageSource::HostileOrganism),
+                        StatusEffects::default(),
+                        ContactEffect(StatusEffect::slow(0.4, Duration::from_secs(3))),
+                    ),
                     SpriteSheetBundle {
-                        texture_atlas: texture_atlas_handle,
+                        texture_atlas: game_assets.item_atlas(ItemKind::YellowOrganism),
                         transform: *p_transform,
                         ..default()
                     },
@@ -469,7 +549,7 @@ pub fn spawn_entity_instances(
                         ResourceNameplate,
                         Text2dBundle {
                             text: Text::from_section("Life Form", TextStyle {
-                                font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+                                font: game_assets.nameplate_font.clone(),
                                 font_size: 16.,
                                 ..default()
                             }),
@@ -479,23 +559,63 @@ pub fn spawn_entity_instances(
                         },
                     ));
                     parent.spawn((
-                        Collider::ball(106.),
+                        Collider::ball(aggro_radius),
+                        ColliderMassProperties::Density(0.),
                         Sensor,
                         ActiveEvents::COLLISION_EVENTS,
                         AggroRange
                     ));
                 });
             }
+            "HazardZone" => {
+                commands.entity(entity).insert(hazard_bundle(instance, *p_transform, &hazard_disc));
+            }
+            // Anything else is reported by `level_validation` when the project loads.
             _ => {}
         }
     }
 }
 
+/// Fields an LDtk entity must have set for `spawn_entity_instances` to build it, or `None` for
+/// identifiers it doesn't know how to spawn.
+pub fn required_fields(identifier: &str) -> Option<&'static [&'static str]> {
+    match identifier {
+        "Player" | "LightSpeed" | "Herbs" | "Gold" | "Element251" | "Water" | "WeaponArtifact"
+        | "ShieldArtifact" | "Organism" | "Hostiles" => Some(&[]),
+        "HazardZone" => Some(&["Kind"]),
+        _ => None,
+    }
+}
+
+fn nameplate_label(name: &str, quantity: u32) -> String {
+    if quantity > 1 { format!("{} x{}", name, quantity) } else { name.to_string() }
+}
+
+/// Staggers idle chirps so organisms don't all call out at once.
+fn chirp_interval(entity: Entity) -> Duration {
+    Duration::from_millis(3000 + (entity.index() % 7) as u64 * 450)
+}
+
+fn tractor_body(mass: f32) -> impl Bundle {
+    (
+        RigidBody::Dynamic,
+        GravityScale(0.),
+        Velocity::zero(),
+        ExternalForce::default(),
+        tractor_damping(),
+        ColliderMassProperties::Mass(mass),
+    )
+}
+
+fn tractor_damping() -> Damping {
+    Damping { linear_damping: 2., angular_damping: 1. }
+}
+
 #[derive(Component)]
 pub struct LightSpeed;
 
 #[derive(Component)]
-pub struct DamageCollider(pub f32);
+pub struct DamageCollider(pub f32, pub DamageSource);
 
 #[derive(Component)]
-pub struct Damage(pub f32, pub Timer);
+pub struct Damage(pub f32, pub Timer, pub DamageSource);
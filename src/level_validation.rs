@@ -0,0 +1,125 @@
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use bevy_ecs_ldtk::{EntityInstance, LdtkAsset};
+use bevy_ecs_ldtk::ldtk::{FieldValue, LdtkJson};
+use crate::level::required_fields;
+
+/// Tileset textures of a freshly loaded LDtk project that haven't finished loading yet.
+#[derive(Resource, Default)]
+pub struct PendingLevelTextures(Vec<(String, Handle<Image>)>);
+
+fn is_empty_value(value: &FieldValue) -> bool {
+    matches!(
+        value,
+        FieldValue::Int(None) | FieldValue::Float(None) | FieldValue::String(None) | FieldValue::FilePath(None)
+            | FieldValue::Enum(None) | FieldValue::Tile(None) | FieldValue::EntityRef(None) | FieldValue::Point(None)
+    )
+}
+
+pub fn validate_entity(level: &str, instance: &EntityInstance) -> Vec<String> {
+    let location = format!("{} ({}, {})", level, instance.grid.x, instance.grid.y);
+    let Some(required) = required_fields(&instance.identifier) else {
+        return vec![format!("{}: unknown entity \"{}\" will not be spawned", location, instance.identifier)];
+    };
+    required.iter()
+        .filter(|identifier| !instance.field_instances.iter()
+            .any(|field| field.identifier == **identifier && !is_empty_value(&field.value)))
+        .map(|identifier| format!("{}: {} is missing required field \"{}\"", location, instance.identifier, identifier))
+        .collect()
+}
+
+/// Problems the game can spot in the project data alone, before any texture has loaded.
+pub fn validate_project(project: &LdtkJson) -> Vec<String> {
+    let mut issues = vec![];
+    for level in project.iter_levels() {
+        for layer in level.layer_instances.iter().flatten() {
+            for instance in layer.entity_instances.iter() {
+                issues.extend(validate_entity(&level.identifier, instance));
+            }
+        }
+    }
+    for tileset in project.defs.tilesets.iter() {
+        if tileset.rel_path.is_none() && tileset.embed_atlas.is_none() {
+            issues.push(format!("tileset {} has no texture path", tileset.identifier));
+        }
+    }
+    issues
+}
+
+fn report(issues: &[String]) {
+    if issues.is_empty() { return; }
+    for issue in issues {
+        warn!("LDtk: {}", issue);
+    }
+    #[cfg(feature = "debug-mode")]
+    panic!("{} problem(s) found in the LDtk project, see the warnings above", issues.len());
+}
+
+pub fn validate_loaded_levels(
+    mut ldtk_events: EventReader<AssetEvent<LdtkAsset>>,
+    ldtk_assets: Res<Assets<LdtkAsset>>,
+    mut pending: ResMut<PendingLevelTextures>,
+) {
+    for event in ldtk_events.iter() {
+        let (AssetEvent::Created { handle } | AssetEvent::Modified { handle }) = event else { continue; };
+        let Some(ldtk) = ldtk_assets.get(handle) else { continue; };
+        report(&validate_project(&ldtk.project));
+        for tileset in ldtk.project.defs.tilesets.iter() {
+            let (Some(path), Some(texture)) = (&tileset.rel_path, ldtk.tileset_map.get(&tileset.uid)) else { continue; };
+            pending.0.push((format!("tileset {} texture \"{}\"", tileset.identifier, path), texture.clone()));
+        }
+    }
+}
+
+pub fn validate_level_textures(asset_server: Res<AssetServer>, mut pending: ResMut<PendingLevelTextures>) {
+    if pending.0.is_empty() { return; }
+    let mut issues = vec![];
+    pending.0.retain(|(description, texture)| match asset_server.get_load_state(texture) {
+        LoadState::Failed | LoadState::Unloaded => {
+            issues.push(format!("{} could not be loaded", description));
+            false
+        }
+        LoadState::Loaded => false,
+        _ => true,
+    });
+    report(&issues);
+}
+
+#[cfg(test)]
+mod level_validation_tests {
+    use bevy_ecs_ldtk::ldtk::FieldValue;
+    use crate::entity_fields::field_instance;
+    use super::*;
+
+    fn instance(identifier: &str, fields: Vec<(&str, FieldValue)>) -> EntityInstance {
+        EntityInstance {
+            identifier: identifier.to_string(),
+            grid: IVec2::new(4, 7),
+            field_instances: fields.into_iter().map(|(identifier, value)| field_instance(identifier, value)).collect(),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn it_reports_unknown_entities_with_their_position() {
+        let issues = validate_entity("Level_0", &instance("Orgnism", vec![]));
+        assert_eq!(issues, vec!["Level_0 (4, 7): unknown entity \"Orgnism\" will not be spawned".to_string()]);
+        assert!(validate_entity("Level_0", &instance("Organism", vec![])).is_empty());
+    }
+
+    #[test]
+    fn it_reports_required_fields_left_empty() {
+        let empty = instance("HazardZone", vec![("Kind", FieldValue::Enum(None))]);
+        assert_eq!(validate_entity("Level_0", &empty).len(), 1);
+        let missing = instance("HazardZone", vec![]);
+        assert_eq!(validate_entity("Level_0", &missing).len(), 1);
+        let set = instance("HazardZone", vec![("Kind", FieldValue::Enum(Some("Acid".to_string())))]);
+        assert!(validate_entity("Level_0", &set).is_empty());
+    }
+
+    #[test]
+    fn it_finds_no_problems_in_the_shipped_levels() {
+        let project: LdtkJson = serde_json::from_str(include_str!("../assets/level.ldtk")).unwrap();
+        assert_eq!(validate_project(&project), Vec::<String>::new());
+    }
+}
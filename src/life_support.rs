@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+use crate::AppState;
+use crate::beams::InteractLightBeam;
+use crate::boost::Boost;
+use crate::damage::{DamageSource, DamageTakenEvent};
+use crate::level::{Health, ItemKind};
+use crate::ship::Ship;
+use crate::ui::InGameUI;
+
+/// How fast the ship's life support (its `Health`) runs down, and how the ship copes when it's low.
+#[derive(Component)]
+pub struct LifeSupport {
+    /// Per second while idling.
+    pub drain_rate: f32,
+    /// Extra per second while boosting.
+    pub boost_drain: f32,
+    /// Extra per second while the tractor beam is on.
+    pub beam_drain: f32,
+    /// Fraction of max life support below which the ship slows down and the HUD warns.
+    pub low_threshold: f32,
+    pub critical_threshold: f32,
+    pub low_speed_multiplier: f32,
+}
+
+impl Default for LifeSupport {
+    fn default() -> Self {
+        LifeSupport {
+            drain_rate: 0.04,
+            boost_drain: 0.08,
+            beam_drain: 0.06,
+            low_threshold: 0.3,
+            critical_threshold: 0.15,
+            low_speed_multiplier: 0.7,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LifeSupportLevel {
+    Normal,
+    Low,
+    Critical,
+}
+
+impl LifeSupport {
+    pub fn drain(&self, boosting: bool, beam_on: bool) -> f32 {
+        let mut drain = self.drain_rate;
+        if boosting { drain += self.boost_drain; }
+        if beam_on { drain += self.beam_drain; }
+        drain
+    }
+
+    pub fn level(&self, health: &Health) -> LifeSupportLevel {
+        if health.max <= 0. { return LifeSupportLevel::Critical; }
+        let fraction = health.current / health.max;
+        if fraction <= self.critical_threshold {
+            LifeSupportLevel::Critical
+        } else if fraction <= self.low_threshold {
+            LifeSupportLevel::Low
+        } else {
+            LifeSupportLevel::Normal
+        }
+    }
+
+    pub fn speed_multiplier(&self, health: &Health) -> f32 {
+        if self.level(health) == LifeSupportLevel::Normal { 1. } else { self.low_speed_multiplier }
+    }
+}
+
+/// Life support restored by using an item from the inventory.
+pub fn restore_amount(kind: ItemKind) -> Option<f32> {
+    match kind {
+        ItemKind::Herbs => Some(2.),
+        ItemKind::Water => Some(1.),
+        _ => None,
+    }
+}
+
+pub fn drain_life_support(
+    time: Res<Time>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut ev_damage_taken: EventWriter<DamageTakenEvent>,
+    beam_q: Query<&Visibility, With<InteractLightBeam>>,
+    mut ship_q: Query<(&LifeSupport, &mut Health, Option<&Boost>), With<Ship>>,
+) {
+    let beam_on = beam_q.iter().any(|visibility| *visibility == Visibility::Visible);
+    for (life_support, mut health, boost) in ship_q.iter_mut() {
+        if health.current <= 0. { continue; }
+        let boosting = boost.is_some_and(|boost| boost.active);
+        let drained = life_support.drain(boosting, beam_on) * time.delta_seconds();
+        health.current -= drained;
+        if health.current <= 0. {
+            health.current = 0.;
+            // The steady drain isn't a hit, only the part that empties life support counts as one.
+            ev_damage_taken.send(DamageTakenEvent(drained, DamageSource::LifeSupport));
+            next_state.set(AppState::GameOver);
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct LifeSupportWarning;
+
+pub fn setup_life_support_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((InGameUI, LifeSupportWarning, TextBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(48.),
+                left: Val::Percent(50.),
+                ..default()
+            },
+            margin: UiRect::left(Val::Px(-160.)),
+            size: Size::width(Val::Px(320.)),
+            ..default()
+        },
+        text: Text::from_section("", TextStyle {
+            font: asset_server.load("fonts/static/JetBrainsMono-Regular.ttf"),
+            font_size: 24.,
+            color: Color::hex("#F2D16B").unwrap(),
+        }).with_alignment(TextAlignment::Center),
+        visibility: Visibility::Hidden,
+        ..default()
+    }));
+}
+
+pub fn life_support_warning_ui(
+    time: Res<Time>,
+    ship_q: Query<(&LifeSupport, &Health), With<Ship>>,
+    mut warning_q: Query<(&mut Text, &mut Visibility), With<LifeSupportWarning>>,
+) {
+    let Ok((life_support, health)) = ship_q.get_single() else { return; };
+    let level = life_support.level(health);
+    for (mut text, mut visibility) in warning_q.iter_mut() {
+        let (message, color, blink_rate) = match level {
+            LifeSupportLevel::Normal => {
+                *visibility = Visibility::Hidden;
+                continue;
+            }
+            LifeSupportLevel::Low => ("Life support low", Color::hex("#F2D16B").unwrap(), 1.),
+            LifeSupportLevel::Critical => ("LIFE SUPPORT CRITICAL", Color::hex("#FF5B5B").unwrap(), 3.),
+        };
+        text.sections[0].value = message.to_string();
+        text.sections[0].style.color = color;
+        let blink_on = (time.elapsed_seconds() * blink_rate).fract() < 0.6;
+        *visibility = if blink_on { Visibility::Visible } else { Visibility::Hidden };
+    }
+}
+
+#[cfg(test)]
+mod life_support_tests {
+    use super::*;
+
+    #[test]
+    fn it_drains_faster_while_boosting_or_beaming() {
+        let life_support = LifeSupport::default();
+        let idle = life_support.drain(false, false);
+        assert!(life_support.drain(true, false) > idle);
+        assert!(life_support.drain(false, true) > idle);
+        assert!(life_support.drain(true, true) > life_support.drain(true, false));
+    }
+
+    #[test]
+    fn it_slows_the_ship_when_running_low() {
+        let life_support = LifeSupport::default();
+        let full = Health::new(10.);
+        let low = Health { current: 2.5, max: 10. };
+        let critical = Health { current: 1., max: 10. };
+        assert_eq!(life_support.level(&full), LifeSupportLevel::Normal);
+        assert_eq!(life_support.level(&low), LifeSupportLevel::Low);
+        assert_eq!(life_support.level(&critical), LifeSupportLevel::Critical);
+        assert_eq!(life_support.speed_multiplier(&full), 1.);
+        assert!(life_support.speed_multiplier(&low) < 1.);
+    }
+
+    #[test]
+    fn it_restores_more_from_herbs_than_water() {
+        assert!(restore_amount(ItemKind::Herbs) > restore_amount(ItemKind::Water));
+        assert!(restore_amount(ItemKind::Water).is_some());
+        assert_eq!(restore_amount(ItemKind::Gold), None);
+    }
+
+    #[test]
+    fn it_reports_running_out_of_life_support_as_the_killing_blow() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_state::<AppState>();
+        app.add_event::<DamageTakenEvent>();
+        app.add_system(drain_life_support);
+        app.world.spawn((Ship, LifeSupport::default(), Health { current: 0.05, max: 10. }));
+        let mut now = bevy::utils::Instant::now();
+        let mut step = |app: &mut App| {
+            now += std::time::Duration::from_secs(1);
+            app.insert_resource(bevy::time::TimeUpdateStrategy::ManualInstant(now));
+            app.update();
+        };
+        step(&mut app);
+        step(&mut app);
+        assert!(app.world.resource::<Events<DamageTakenEvent>>().is_empty());
+
+        step(&mut app);
+        let events = app.world.resource::<Events<DamageTakenEvent>>();
+        let sources: Vec<DamageSource> = events.iter_current_update_events().map(|ev| ev.1).collect();
+        assert_eq!(sources, vec![DamageSource::LifeSupport]);
+        assert_eq!(app.world.resource::<NextState<AppState>>().0, Some(AppState::GameOver));
+    }
+}
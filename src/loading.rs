@@ -0,0 +1,282 @@
+use bevy::asset::{HandleUntyped, LoadState};
+use bevy::prelude::*;
+use bevy_ecs_ldtk::LdtkAsset;
+use crate::AppState;
+use crate::audio::Sound;
+use crate::game_assets::GameAssets;
+use crate::music::MusicTrack;
+
+#[derive(Component)]
+pub struct LoadingUI;
+
+#[derive(Component)]
+pub struct LoadingBar;
+
+#[derive(Component)]
+pub struct LoadingText;
+
+/// Handles to every file the game needs, requested before the main menu so nothing streams in
+/// mid-game: everything in [`GameAssets`], the level and, once the level has loaded, the tilesets
+/// it draws with. Holding them keeps the assets alive for the whole session.
+#[derive(Resource)]
+pub struct AssetPreflight {
+    level: Handle<LdtkAsset>,
+    tilesets_added: bool,
+    assets: Vec<(HandleUntyped, bool)>,
+}
+
+impl FromWorld for AssetPreflight {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        let level: Handle<LdtkAsset> = asset_server.load("level.ldtk");
+        let required = world.resource::<GameAssets>().files(world.resource::<Assets<TextureAtlas>>())
+            .into_iter()
+            .chain([level.clone_untyped()]);
+        // Audio is optional: the game plays on in silence when a sound is missing.
+        let optional = MusicTrack::ALL.iter().map(|track| track.path())
+            .chain(Sound::ALL.iter().map(|sound| sound.path()))
+            .map(|path| asset_server.load_untyped(path));
+        let mut preflight = AssetPreflight { level, tilesets_added: false, assets: vec![] };
+        for (handle, required) in required.map(|handle| (handle, true)).chain(optional.map(|handle| (handle, false))) {
+            preflight.add(handle, required);
+        }
+        preflight
+    }
+}
+
+impl AssetPreflight {
+    /// Several assets can share a file, e.g. an item's icon and its sprite sheet; it's listed once.
+    fn add(&mut self, handle: HandleUntyped, required: bool) {
+        if self.assets.iter().any(|(listed, _)| listed.id() == handle.id()) { return; }
+        self.assets.push((handle, required));
+    }
+
+    /// The level only says which tilesets it needs once it has loaded, so they join the list late.
+    pub fn add_level_tilesets(&mut self, levels: &Assets<LdtkAsset>) {
+        if self.tilesets_added { return; }
+        let Some(level) = levels.get(&self.level) else { return; };
+        for tileset in level.tileset_map.values() {
+            self.add(tileset.clone_untyped(), true);
+        }
+        self.tilesets_added = true;
+    }
+
+    pub fn progress(&self, asset_server: &AssetServer) -> PreflightProgress {
+        PreflightProgress::from_states(self.assets.iter().map(|(handle, required)| {
+            let path = asset_server.get_handle_path(handle.id())
+                .map_or_else(|| format!("{:?}", handle.id()), |path| path.path().display().to_string());
+            let state = match asset_server.get_load_state(handle) {
+                // Not done until its tilesets are on the list too.
+                LoadState::Loaded if handle.id() == self.level.id() && !self.tilesets_added => LoadState::Loading,
+                state => state,
+            };
+            (path, *required, state)
+        }))
+    }
+}
+
+#[derive(Default, PartialEq, Debug)]
+pub struct PreflightProgress {
+    pub done: usize,
+    pub total: usize,
+    /// Required assets that failed to load.
+    pub failed: Vec<String>,
+    /// Optional assets that failed to load; the game runs without them.
+    pub missing: Vec<String>,
+}
+
+impl PreflightProgress {
+    pub fn from_states(states: impl Iterator<Item = (String, bool, LoadState)>) -> Self {
+        let mut progress = PreflightProgress::default();
+        for (path, required, state) in states {
+            progress.total += 1;
+            match state {
+                LoadState::Loaded => progress.done += 1,
+                LoadState::Failed | LoadState::Unloaded => {
+                    progress.done += 1;
+                    if required { progress.failed.push(path); } else { progress.missing.push(path); }
+                }
+                LoadState::NotLoaded | LoadState::Loading => {}
+            }
+        }
+        progress
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 { 1. } else { self.done as f32 / self.total as f32 }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.done == self.total
+    }
+}
+
+pub fn setup_loading_screen(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands.spawn((LoadingUI, NodeBundle {
+        style: Style {
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+            ..default()
+        },
+        ..default()
+    })).with_children(|parent| {
+        parent.spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                gap: Size::height(Val::Px(16.)),
+                ..default()
+            },
+            ..default()
+        }).with_children(|parent| {
+            parent.spawn(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Px(320.), Val::Px(12.)),
+                    ..default()
+                },
+                background_color: Color::hex("#333").unwrap().into(),
+                ..default()
+            }).with_children(|parent| {
+                parent.spawn((LoadingBar, NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(0.), Val::Percent(100.)),
+                        ..default()
+                    },
+                    background_color: Color::hex("#9FF4FF").unwrap().into(),
+                    ..default()
+                }));
+            });
+            parent.spawn((LoadingText, TextBundle::from_section(
+                "Loading",
+                TextStyle {
+                    font: game_assets.text_font.clone(),
+                    font_size: 20.,
+                    color: Color::hex("#FFF").unwrap(),
+                },
+            ).with_text_alignment(TextAlignment::Center)));
+        });
+    });
+}
+
+pub fn track_level_tilesets(mut preflight: ResMut<AssetPreflight>, levels: Res<Assets<LdtkAsset>>) {
+    preflight.add_level_tilesets(&levels);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_loading_screen(
+    mut report: Local<Option<PreflightProgress>>,
+    asset_server: Res<AssetServer>,
+    preflight: Res<AssetPreflight>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut bar_q: Query<&mut Style, With<LoadingBar>>,
+    mut text_q: Query<&mut Text, With<LoadingText>>,
+) {
+    if let Some(report) = report.as_ref() {
+        let pressed = keys.get_just_pressed().next().is_some()
+            || mouse.get_just_pressed().next().is_some()
+            || gamepad_buttons.get_just_pressed().next().is_some();
+        if report.failed.is_empty() && pressed {
+            next_state.set(AppState::MainMenu);
+        }
+        return;
+    }
+    let progress = preflight.progress(&asset_server);
+    for mut style in bar_q.iter_mut() {
+        style.size.width = Val::Percent(progress.fraction() * 100.);
+    }
+    if !progress.is_finished() {
+        for mut text in text_q.iter_mut() {
+            text.sections[0].value = format!("Loading {} / {}", progress.done, progress.total);
+        }
+        return;
+    }
+    if progress.failed.is_empty() && progress.missing.is_empty() {
+        next_state.set(AppState::MainMenu);
+        return;
+    }
+    for path in progress.failed.iter() {
+        error!("Required asset {} could not be loaded", path);
+    }
+    for mut text in text_q.iter_mut() {
+        if progress.failed.is_empty() {
+            text.sections[0].value = format!(
+                "Some sounds could not be loaded and will be silent:\n\n{}\n\nPress any key to continue.",
+                progress.missing.join("\n"),
+            );
+            text.sections[0].style.color = Color::hex("#F2D16B").unwrap();
+        } else {
+            text.sections[0].value = format!(
+                "Some game files could not be loaded:\n\n{}\n\nCheck that the assets folder is complete and restart the game.",
+                progress.failed.join("\n"),
+            );
+            text.sections[0].style.color = Color::hex("#FF5B5B").unwrap();
+        }
+    }
+    *report = Some(progress);
+}
+
+#[cfg(test)]
+mod loading_tests {
+    use bevy::asset::AssetPlugin;
+    use bevy_ecs_ldtk::ldtk::LdtkJson;
+    use super::*;
+
+    fn states<const N: usize>(states: [(&str, bool, LoadState); N]) -> impl Iterator<Item = (String, bool, LoadState)> + '_ {
+        states.into_iter().map(|(path, required, state)| (path.to_string(), required, state))
+    }
+
+    #[test]
+    fn it_waits_until_every_asset_settles() {
+        let progress = PreflightProgress::from_states(states([
+            ("level.ldtk", true, LoadState::Loaded),
+            ("artifact.png", true, LoadState::Loading),
+            ("sounds/boost.mp3", false, LoadState::NotLoaded),
+        ]));
+        assert_eq!(progress.done, 1);
+        assert_eq!(progress.total, 3);
+        assert!(!progress.is_finished());
+    }
+
+    #[test]
+    fn it_only_fails_on_missing_required_assets() {
+        let progress = PreflightProgress::from_states(states([
+            ("level.ldtk", true, LoadState::Loaded),
+            ("artifact.png", true, LoadState::Failed),
+            ("sounds/boost.mp3", false, LoadState::Failed),
+        ]));
+        assert!(progress.is_finished());
+        assert_eq!(progress.fraction(), 1.);
+        assert_eq!(progress.failed, vec!["artifact.png"]);
+        assert_eq!(progress.missing, vec!["sounds/boost.mp3"]);
+    }
+
+    #[test]
+    fn it_ships_every_file_the_game_assets_load() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugin(AssetPlugin::default());
+        app.add_asset::<Image>();
+        app.add_asset::<TextureAtlas>();
+        app.init_resource::<GameAssets>();
+
+        let files = app.world.resource::<GameAssets>().files(app.world.resource::<Assets<TextureAtlas>>());
+        let asset_server = app.world.resource::<AssetServer>();
+        assert!(!files.is_empty());
+        for file in files {
+            let path = asset_server.get_handle_path(file.id()).unwrap();
+            assert!(std::path::Path::new("assets").join(path.path()).exists(), "{} is missing", path.path().display());
+        }
+    }
+
+    #[test]
+    fn it_ships_every_tileset_the_level_draws_with() {
+        let project: LdtkJson = serde_json::from_str(include_str!("../assets/level.ldtk")).unwrap();
+        for path in project.defs.tilesets.iter().filter_map(|tileset| tileset.rel_path.as_ref()) {
+            assert!(std::path::Path::new("assets").join(path).exists(), "{} is missing", path);
+        }
+    }
+}
@@ -1,19 +1,9 @@
-use std::time::Duration;
 use bevy::prelude::*;
-use bevy_kira_audio::AudioPlugin;
-use bevy::window::PrimaryWindow;
-use bevy_ecs_ldtk::{LdtkPlugin, LdtkWorldBundle, LevelSelection};
+use bevy_kira_audio::{AudioApp, AudioPlugin};
+use bevy_ecs_ldtk::{LdtkPlugin, LevelSelection};
 use bevy_rapier2d::prelude::*;
-use aggro::Aggro;
-use animation::Animation;
-use beams::{BeamUpEvent, InteractLightBeam, UnderBeamItems};
-use camera::MainCamera;
-use level::{Damage, DamageCollider};
-use movement::Speed;
-use ship::Ship;
-use crate::audio::start_background_audio;
-use crate::level::{AggroRange, Health, Inventory, Item, ResourceNameplate};
-use crate::ui::{GameOverUI, InGameUI, IntroUI, inventory_interactions, inventory_ui, MainMenuUI, PanelMainText, PanelText};
+use beams::{BeamUpEvent, UnderBeamItems};
+use crate::ui::{GameOverUI, InGameUI, IntroUI, inventory_interactions, inventory_ui, MainMenuUI};
 
 mod ui;
 mod level;
@@ -26,10 +16,32 @@ mod ship;
 mod aggro;
 mod damage;
 mod cursor;
+mod boost;
+mod crafting;
+mod effects;
+mod settings;
+mod settings_menu;
+mod storage;
+mod minimap;
+mod indicators;
+mod scanner;
+mod music;
+mod positional_audio;
+mod run;
+mod stats;
+mod leaderboard;
+mod life_support;
+mod status_effects;
+mod hazards;
+mod entity_fields;
+mod level_validation;
+mod loading;
+mod game_assets;
 
 #[derive(States, Clone, PartialEq, Eq, Debug, Hash, Default)]
 pub enum AppState {
     #[default]
+    Loading,
     MainMenu,
     Intro,
     InGame,
@@ -60,54 +72,129 @@ fn main() {
         app.add_plugin(WorldInspectorPlugin::new());
         app.add_plugin(RapierDebugRenderPlugin::default());
     }
-    app.add_system(start_background_audio.on_startup());
+    app.add_audio_channel::<audio::MusicChannel>();
+    app.add_audio_channel::<audio::SfxChannel>();
+    app.add_audio_channel::<audio::UiChannel>();
+    app.init_resource::<audio::SoundLibrary>();
+    app.init_resource::<music::MusicPlayer>();
+    app.init_resource::<positional_audio::LoopingEmitters>();
+    app.add_systems((audio::report_missing_audio, audio::apply_volume_settings, audio::play_button_sounds));
+    app.add_systems((music::play_state_music, music::music_intensity).chain());
+    app.add_systems((positional_audio::emit_world_sounds, positional_audio::spatialize_emitters).chain().after(aggro::handle_aggro).in_set(OnUpdate(AppState::InGame)));
+    app.add_system(positional_audio::stop_removed_emitters);
+    app.add_system(positional_audio::stop_all_emitters.in_schedule(OnExit(AppState::InGame)));
+    app.add_system(audio::play_gameplay_sounds.after(beams::tractor_collect).after(damage::handle_damage).in_set(OnUpdate(AppState::InGame)));
+    app.add_system(audio::play_game_over_sound.in_schedule(OnEnter(AppState::GameOver)));
     app.add_state::<AppState>();
+    app.add_state::<settings_menu::MenuOverlay>();
+    app.init_resource::<settings_menu::AwaitingBinding>();
     app.insert_resource(UnderBeamItems(vec![]));
+    app.init_resource::<camera::LevelBounds>();
+    app.insert_resource(settings::load_settings());
+    app.init_resource::<effects::ScreenShakeSettings>();
+    app.init_resource::<effects::HitStop>();
+    app.init_resource::<minimap::Minimap>();
+    app.init_resource::<stats::RunStats>();
+    app.init_resource::<run::RunStart>();
+    app.init_resource::<scanner::ScannerRing>();
+    app.init_resource::<hazards::HazardDisc>();
+    app.init_resource::<game_assets::GameAssets>();
+    app.init_resource::<level_validation::PendingLevelTextures>();
+    app.init_resource::<loading::AssetPreflight>();
+    app.insert_resource(minimap::FogOfWar::load());
+    app.insert_resource(leaderboard::Leaderboard::load());
+    app.add_system(camera::setup_main_camera.in_schedule(OnEnter(AppState::Loading)));
+    app.add_system(loading::setup_loading_screen.in_schedule(OnEnter(AppState::Loading)));
+    app.add_system(loading::track_level_tilesets.in_set(OnUpdate(AppState::Loading)).before(loading::update_loading_screen));
+    app.add_system(loading::update_loading_screen.in_set(OnUpdate(AppState::Loading)));
+    app.add_system(ui::clean_up_ui::<loading::LoadingUI>.in_schedule(OnExit(AppState::Loading)));
     app.add_system(setup_start_menu.in_schedule(OnEnter(AppState::MainMenu)));
-    app.add_system(camera::setup_main_camera.in_schedule(OnEnter(AppState::MainMenu)));
     app.add_system(ui::menu_button_interactions_system.in_set(OnUpdate(AppState::MainMenu)));
     app.add_system(ui::clean_up_ui::<MainMenuUI>.in_schedule(OnExit(AppState::MainMenu)));
     app.add_system(ui::setup_intro.in_schedule(OnEnter(AppState::Intro)));
-    app.add_system(ui::dialog_interaction_system.in_set(OnUpdate(AppState::Intro)));
-    app.add_systems((ui::clean_up_ui::<IntroUI>, ui::load_level).chain().in_schedule(OnExit(AppState::Intro)));
+    app.add_systems((ui::dialog_interaction_system, ui::typewriter_system).chain().in_set(OnUpdate(AppState::Intro)));
+    app.add_system(ui::clean_up_ui::<IntroUI>.in_schedule(OnExit(AppState::Intro)));
+    app.add_system(run::start_new_run.in_schedule(OnEnter(AppState::InGame)));
     app.add_system(ui::setup_game_ui.in_schedule(OnEnter(AppState::InGame)));
     app.add_system(ui::health_ui.in_set(OnUpdate(AppState::InGame)));
-    app.add_system(camera::camera_follow_ship.in_set(OnUpdate(AppState::InGame)));
-    app.add_systems((damage::handle_collisions, beams::beam_up).chain().in_set(OnUpdate(AppState::InGame)));
+    app.add_system(life_support::setup_life_support_ui.in_schedule(OnEnter(AppState::InGame)));
+    app.add_system(status_effects::setup_status_ui.in_schedule(OnEnter(AppState::InGame)));
+    app.add_system(hazards::setup_hazard_tint.in_schedule(OnEnter(AppState::InGame)));
+    app.add_system(status_effects::status_icons_ui.after(status_effects::tick_status_effects).in_set(OnUpdate(AppState::InGame)));
+    app.add_system(life_support::life_support_warning_ui.after(life_support::drain_life_support).in_set(OnUpdate(AppState::InGame)));
+    app.add_system(ui::boost_ui.in_set(OnUpdate(AppState::InGame)));
+    app.add_system(ui::beam_energy_ui.in_set(OnUpdate(AppState::InGame)));
+    app.add_system(beams::element_increases_beam_energy.after(beams::tractor_collect).in_set(OnUpdate(AppState::InGame)));
+    app.add_systems((ui::beam_upgrade_interactions, ui::beam_level_ui).in_set(OnUpdate(AppState::InGame)));
+    app.add_systems((ui::toggle_crafting_panel.run_if(in_state(settings_menu::MenuOverlay::Closed)), ui::crafting_ui, ui::crafting_interactions, ui::artifact_activated_ui).chain().in_set(OnUpdate(AppState::InGame)));
+    app.add_systems((
+        effects::remove_camera_shake,
+        camera::camera_follow_ship.in_set(OnUpdate(AppState::InGame)),
+        effects::apply_camera_shake,
+    ).chain());
+    app.add_system(effects::shake_on_gameplay_events.after(beams::tractor_collect).after(damage::handle_damage));
+    app.add_system(effects::update_hit_stop);
+    app.add_systems((damage::handle_collisions, beams::tractor_beam, beams::tractor_collect).chain().in_set(OnUpdate(AppState::InGame)));
     app.add_systems((aggro::handle_aggro, aggro::aggro_movement).chain().in_set(OnUpdate(AppState::InGame)));
     app.add_systems((damage::handle_collision_damage, damage::handle_damage).chain().in_set(OnUpdate(AppState::InGame)));
+    app.add_system(life_support::drain_life_support.after(damage::handle_damage).in_set(OnUpdate(AppState::InGame)));
+    app.add_systems((status_effects::apply_contact_effects, status_effects::artifact_side_effects, status_effects::tick_status_effects).chain().after(damage::handle_damage).in_set(OnUpdate(AppState::InGame)));
+    app.add_systems((hazards::hazard_occupancy, hazards::hazard_effects, hazards::hazard_tint).chain().after(damage::handle_damage).in_set(OnUpdate(AppState::InGame)));
+    app.add_system(stats::track_run_stats.after(beams::tractor_collect).after(life_support::drain_life_support).after(status_effects::tick_status_effects).after(hazards::hazard_effects).after(aggro::handle_aggro).in_set(OnUpdate(AppState::InGame)));
     app.add_system(ui::clean_up_ui::<InGameUI>.in_schedule(OnExit(AppState::InGame)));
-    app.add_system(ui::setup_game_over.in_schedule(OnEnter(AppState::GameOver)));
-    app.add_system(ui::menu_button_interactions_system.in_set(OnUpdate(AppState::GameOver)));
+    app.add_systems((leaderboard::record_run, ui::setup_game_over).chain().in_schedule(OnEnter(AppState::GameOver)));
+    app.add_systems((ui::menu_button_interactions_system, ui::retry_level_interactions).in_set(OnUpdate(AppState::GameOver)));
     app.add_system(ui::clean_up_ui::<GameOverUI>.in_schedule(OnExit(AppState::GameOver)));
     app.add_event::<BeamUpEvent>();
-    app.add_system(inventory_ui.in_set(OnUpdate(AppState::InGame)));
+    app.add_event::<crafting::ArtifactActivatedEvent>();
+    app.add_event::<damage::DamageTakenEvent>();
+    app.add_system(inventory_ui.after(beams::tractor_collect).in_set(OnUpdate(AppState::InGame)));
     app.add_systems((ui::panel_text_update, inventory_interactions).chain().in_set(OnUpdate(AppState::InGame)));
     app.add_system(camera::position_camera_at_ship);
+    app.add_system(camera::update_level_bounds);
+    app.add_system(minimap::build_minimap);
+    app.add_system(minimap::setup_minimap_ui.in_schedule(OnEnter(AppState::InGame)));
+    app.add_systems((minimap::reveal_fog, minimap::paint_minimap, minimap::save_fog).chain().in_set(OnUpdate(AppState::InGame)));
+    app.add_systems((indicators::discover_on_screen_targets, indicators::offscreen_indicators).chain().after(camera::camera_follow_ship).in_set(OnUpdate(AppState::InGame)));
+    app.add_systems((scanner::scanner_input.run_if(in_state(settings_menu::MenuOverlay::Closed)), scanner::scanner_ping, scanner::expire_scanned).chain().before(indicators::offscreen_indicators).in_set(OnUpdate(AppState::InGame)));
+    app.add_system(minimap::save_fog_on_exit.in_schedule(OnExit(AppState::InGame)));
+    app.add_systems((camera::zoom_input.run_if(in_state(settings_menu::MenuOverlay::Closed)), camera::zoom_camera).chain().in_set(OnUpdate(AppState::InGame)));
+    app.add_systems((settings::save_settings, settings::apply_window_settings, settings::apply_gameplay_settings));
+    app.add_systems((settings_menu::toggle_pause.in_set(OnUpdate(AppState::InGame)), settings_menu::capture_binding).chain());
+    app.add_system(settings_menu::open_settings_interactions.in_set(OnUpdate(AppState::MainMenu)));
+    app.add_system(settings_menu::close_overlay.in_schedule(OnExit(AppState::MainMenu)));
+    app.add_system(settings_menu::close_overlay.in_schedule(OnExit(AppState::InGame)));
+    app.add_system(settings_menu::pause_game.in_schedule(OnExit(settings_menu::MenuOverlay::Closed)));
+    app.add_system(settings_menu::resume_game.in_schedule(OnEnter(settings_menu::MenuOverlay::Closed)));
+    app.add_system(settings_menu::setup_pause_menu.in_schedule(OnEnter(settings_menu::MenuOverlay::Paused)));
+    app.add_system(settings_menu::pause_menu_interactions.in_set(OnUpdate(settings_menu::MenuOverlay::Paused)));
+    app.add_system(ui::clean_up_ui::<settings_menu::PauseUI>.in_schedule(OnExit(settings_menu::MenuOverlay::Paused)));
+    app.add_system(settings_menu::setup_settings_menu.in_schedule(OnEnter(settings_menu::MenuOverlay::Settings)));
+    app.add_systems((settings_menu::settings_menu_interactions, settings_menu::settings_menu_ui).chain().in_set(OnUpdate(settings_menu::MenuOverlay::Settings)));
+    app.add_systems((ui::clean_up_ui::<settings_menu::SettingsUI>, settings_menu::cancel_binding).in_schedule(OnExit(settings_menu::MenuOverlay::Settings)));
     app.add_system(movement::movement_input);
     app.add_system(level::spawn_entity_instances);
+    app.add_systems((level_validation::validate_loaded_levels, level_validation::validate_level_textures).chain());
     app.add_system(cursor::my_cursor_system);
-    app.add_system(beams::beam_input);
-    app.add_system(beams::boost_input);
+    app.add_systems((beams::beam_input.run_if(in_state(settings_menu::MenuOverlay::Closed)), beams::beam_energy).chain());
+    app.add_system(boost::boost_input.run_if(in_state(settings_menu::MenuOverlay::Closed)).before(movement::movement_input));
+    app.add_system(beams::apply_beam_level);
     app.add_system(animation::animation_system);
     app.run();
 }
 
 #[derive(Resource)]
+#[allow(dead_code)]
 struct LdtkImageHolder(Handle<Image>);
 
 fn setup_start_menu(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    leaderboard: Res<leaderboard::Leaderboard>,
 ) {
-    commands.spawn(LdtkWorldBundle {
-        ldtk_handle: asset_server.load("level.ldtk"),
-        ..Default::default()
-    });
+    run::spawn_world(&mut commands, &asset_server);
     // bug workaround: https://github.com/Trouv/bevy_ecs_ldtk/issues/111
     commands.insert_resource(LdtkImageHolder(asset_server.load("Laser Lvl 1.png")));
     commands.insert_resource(LevelSelection::Index(1));
-    ui::create_main_menu(&mut commands, &asset_server);
+    ui::create_main_menu(&mut commands, &asset_server, &leaderboard);
 }
-
-
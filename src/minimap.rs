@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy_ecs_ldtk::{LdtkLevel, LevelEvent};
+use serde::{Deserialize, Serialize};
+use crate::level::{Item, ItemKind};
+use crate::ship::Ship;
+use crate::storage;
+use crate::ui::InGameUI;
+
+const MINIMAP_WIDTH: f32 = 220.;
+const REVEAL_RADIUS: f32 = 160.;
+const PATH_LAYER: &str = "Path";
+const FOG_KEY: &str = "exploration";
+const GROUND_COLOR: [u8; 4] = [46, 36, 56, 255];
+const PATH_COLOR: [u8; 4] = [107, 90, 122, 255];
+const FOG_COLOR: [u8; 4] = [11, 7, 16, 255];
+const SHIP_COLOR: [u8; 4] = [255, 255, 255, 255];
+const RESOURCE_COLOR: [u8; 4] = [120, 230, 140, 255];
+const ORGANISM_COLOR: [u8; 4] = [230, 80, 80, 255];
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct MinimapGrid {
+    pub origin: Vec2,
+    pub width: usize,
+    pub height: usize,
+    pub cell_size: f32,
+}
+
+impl MinimapGrid {
+    /// Column and row (from the top, like LDtk and image data) of the cell containing `position`.
+    pub fn cell_at(&self, position: Vec2) -> Option<(usize, usize)> {
+        if self.cell_size <= 0. { return None; }
+        let local = (position - self.origin) / self.cell_size;
+        if local.x < 0. || local.y < 0. { return None; }
+        let (column, row_from_bottom) = (local.x as usize, local.y as usize);
+        if column >= self.width || row_from_bottom >= self.height { return None; }
+        Some((column, self.height - 1 - row_from_bottom))
+    }
+
+    pub fn index(&self, (column, row): (usize, usize)) -> usize {
+        row * self.width + column
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.width * self.height
+    }
+}
+
+#[derive(Resource)]
+pub struct Minimap {
+    pub image: Handle<Image>,
+    pub level_iid: Option<String>,
+    pub grid: MinimapGrid,
+    terrain: Vec<[u8; 4]>,
+    repaint: Timer,
+}
+
+impl FromWorld for Minimap {
+    fn from_world(world: &mut World) -> Self {
+        let mut images = world.resource_mut::<Assets<Image>>();
+        Minimap {
+            image: images.add(minimap_image(1, 1)),
+            level_iid: None,
+            grid: MinimapGrid::default(),
+            terrain: vec![],
+            repaint: Timer::from_seconds(0.1, TimerMode::Repeating),
+        }
+    }
+}
+
+impl Minimap {
+    /// Forgets the current level until the next one is transformed into place.
+    pub fn reset(&mut self) {
+        self.level_iid = None;
+        self.grid = MinimapGrid::default();
+        self.terrain.clear();
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct FogOfWar {
+    pub levels: HashMap<String, Vec<bool>>,
+    dirty: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct SavedFog {
+    levels: HashMap<String, String>,
+}
+
+impl FogOfWar {
+    pub fn load() -> Self {
+        let Some(text) = storage::read(FOG_KEY) else { return FogOfWar::default(); };
+        match ron::from_str::<SavedFog>(&text) {
+            Ok(saved) => FogOfWar {
+                levels: saved.levels.into_iter()
+                    .map(|(iid, cells)| (iid, decode_cells(&cells)))
+                    .collect(),
+                dirty: false,
+            },
+            Err(error) => {
+                warn!("Ignoring malformed exploration data: {}", error);
+                FogOfWar::default()
+            }
+        }
+    }
+
+    pub fn save(&mut self) {
+        let saved = SavedFog {
+            levels: self.levels.iter()
+                .map(|(iid, cells)| (iid.clone(), encode_cells(cells)))
+                .collect(),
+        };
+        match ron::to_string(&saved) {
+            Ok(text) => storage::write(FOG_KEY, &text),
+            Err(error) => warn!("Could not serialize exploration data: {}", error),
+        }
+        self.dirty = false;
+    }
+
+    pub fn is_revealed(&self, level_iid: &str, index: usize) -> bool {
+        self.levels.get(level_iid).and_then(|cells| cells.get(index)).copied().unwrap_or(false)
+    }
+
+    /// Reveals every cell within `radius` of `position`. Returns true if anything new was revealed.
+    pub fn reveal(&mut self, level_iid: &str, grid: &MinimapGrid, position: Vec2, radius: f32) -> bool {
+        let cells = self.levels.entry(level_iid.to_string()).or_default();
+        cells.resize(grid.cell_count(), false);
+        let reach = (radius / grid.cell_size).ceil() as i32;
+        let mut revealed = false;
+        for dx in -reach..=reach {
+            for dy in -reach..=reach {
+                let offset = Vec2::new(dx as f32, dy as f32) * grid.cell_size;
+                if offset.length() > radius { continue; }
+                if let Some(cell) = grid.cell_at(position + offset) {
+                    let index = grid.index(cell);
+                    if !cells[index] {
+                        cells[index] = true;
+                        revealed = true;
+                    }
+                }
+            }
+        }
+        self.dirty |= revealed;
+        revealed
+    }
+}
+
+/// Packs revealed cells four to a hex digit.
+fn encode_cells(cells: &[bool]) -> String {
+    let mut text = format!("{}:", cells.len());
+    for chunk in cells.chunks(4) {
+        let nibble = chunk.iter().enumerate()
+            .fold(0u32, |nibble, (bit, revealed)| nibble | ((*revealed as u32) << bit));
+        text.push(char::from_digit(nibble, 16).unwrap());
+    }
+    text
+}
+
+fn decode_cells(text: &str) -> Vec<bool> {
+    let Some((len, digits)) = text.split_once(':') else { return vec![]; };
+    let len = len.parse().unwrap_or(0);
+    let mut cells: Vec<bool> = digits.chars()
+        .flat_map(|digit| {
+            let nibble = digit.to_digit(16).unwrap_or(0);
+            (0..4).map(move |bit| nibble & (1 << bit) != 0)
+        })
+        .collect();
+    cells.resize(len, false);
+    cells
+}
+
+fn minimap_image(width: u32, height: u32) -> Image {
+    Image::new_fill(
+        Extent3d { width, height, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        &FOG_COLOR,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+#[derive(Component)]
+pub struct MinimapNode;
+
+pub fn build_minimap(
+    mut level_events: EventReader<LevelEvent>,
+    level_q: Query<(&Handle<LdtkLevel>, &GlobalTransform)>,
+    levels: Res<Assets<LdtkLevel>>,
+    mut images: ResMut<Assets<Image>>,
+    mut minimap: ResMut<Minimap>,
+) {
+    for level_event in level_events.iter() {
+        let LevelEvent::Transformed(iid) = level_event else { continue; };
+        for (level_handle, level_transform) in level_q.iter() {
+            let Some(ldtk_level) = levels.get(level_handle) else { continue; };
+            if ldtk_level.level.iid != *iid { continue; }
+            let Some(path_layer) = ldtk_level.level.layer_instances.iter().flatten()
+                .find(|layer| layer.identifier == PATH_LAYER) else {
+                warn!("Level {} has no {} layer to draw the minimap from", ldtk_level.level.identifier, PATH_LAYER);
+                continue;
+            };
+            let grid = MinimapGrid {
+                origin: level_transform.translation().truncate(),
+                width: path_layer.c_wid as usize,
+                height: path_layer.c_hei as usize,
+                cell_size: path_layer.grid_size as f32,
+            };
+            minimap.terrain = (0..grid.cell_count())
+                .map(|index| match path_layer.int_grid_csv.get(index) {
+                    Some(value) if *value != 0 => PATH_COLOR,
+                    _ => GROUND_COLOR,
+                })
+                .collect();
+            minimap.grid = grid;
+            minimap.level_iid = Some(iid.clone());
+            if let Some(image) = images.get_mut(&minimap.image) {
+                *image = minimap_image(grid.width as u32, grid.height as u32);
+            }
+        }
+    }
+}
+
+pub fn reveal_fog(
+    ship_q: Query<&GlobalTransform, With<Ship>>,
+    minimap: Res<Minimap>,
+    mut fog: ResMut<FogOfWar>,
+) {
+    let Some(level_iid) = &minimap.level_iid else { return; };
+    for ship_transform in ship_q.iter() {
+        fog.reveal(level_iid, &minimap.grid, ship_transform.translation().truncate(), REVEAL_RADIUS);
+    }
+}
+
+pub fn paint_minimap(
+    time: Res<Time>,
+    mut minimap: ResMut<Minimap>,
+    fog: Res<FogOfWar>,
+    mut images: ResMut<Assets<Image>>,
+    ship_q: Query<&GlobalTransform, With<Ship>>,
+    item_q: Query<(&GlobalTransform, &Item)>,
+    mut node_q: Query<&mut Style, With<MinimapNode>>,
+) {
+    minimap.repaint.tick(time.delta());
+    if !minimap.repaint.just_finished() { return; }
+    let Some(level_iid) = &minimap.level_iid else { return; };
+    let grid = minimap.grid;
+    let Some(image) = images.get_mut(&minimap.image) else { return; };
+    if image.data.len() != grid.cell_count() * 4 { return; }
+    for (index, terrain) in minimap.terrain.iter().enumerate() {
+        let color = if fog.is_revealed(level_iid, index) { terrain } else { &FOG_COLOR };
+        image.data[index * 4..index * 4 + 4].copy_from_slice(color);
+    }
+    let mut paint = |position: Vec2, color: [u8; 4], only_revealed: bool| {
+        let Some(cell) = grid.cell_at(position) else { return; };
+        let index = grid.index(cell);
+        if only_revealed && !fog.is_revealed(level_iid, index) { return; }
+        image.data[index * 4..index * 4 + 4].copy_from_slice(&color);
+    };
+    for (item_transform, item) in item_q.iter() {
+        let color = match item.kind {
+            ItemKind::Organism | ItemKind::YellowOrganism => ORGANISM_COLOR,
+            _ => RESOURCE_COLOR,
+        };
+        paint(item_transform.translation().truncate(), color, true);
+    }
+    for ship_transform in ship_q.iter() {
+        let position = ship_transform.translation().truncate();
+        for offset in [Vec2::ZERO, Vec2::X, Vec2::NEG_X, Vec2::Y, Vec2::NEG_Y] {
+            paint(position + offset * grid.cell_size, SHIP_COLOR, false);
+        }
+    }
+    for mut style in node_q.iter_mut() {
+        let aspect = grid.height as f32 / grid.width.max(1) as f32;
+        style.size = Size::new(Val::Px(MINIMAP_WIDTH), Val::Px(MINIMAP_WIDTH * aspect));
+    }
+}
+
+pub fn save_fog(time: Res<Time>, mut save_timer: Local<Option<Timer>>, mut fog: ResMut<FogOfWar>) {
+    let timer = save_timer.get_or_insert_with(|| Timer::from_seconds(5., TimerMode::Repeating));
+    timer.tick(time.delta());
+    if timer.just_finished() && fog.dirty {
+        fog.save();
+    }
+}
+
+pub fn save_fog_on_exit(mut fog: ResMut<FogOfWar>) {
+    if fog.dirty {
+        fog.save();
+    }
+}
+
+pub fn setup_minimap_ui(mut commands: Commands, minimap: Res<Minimap>) {
+    commands.spawn((InGameUI, MinimapNode, ImageBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(8.),
+                top: Val::Px(8.),
+                ..default()
+            },
+            size: Size::new(Val::Px(MINIMAP_WIDTH), Val::Px(MINIMAP_WIDTH)),
+            ..default()
+        },
+        image: UiImage::new(minimap.image.clone()),
+        background_color: Color::rgba(1., 1., 1., 0.85).into(),
+        ..default()
+    }));
+}
+
+#[cfg(test)]
+mod minimap_tests {
+    use super::*;
+
+    fn grid() -> MinimapGrid {
+        MinimapGrid { origin: Vec2::new(100., 100.), width: 10, height: 5, cell_size: 16. }
+    }
+
+    #[test]
+    fn it_maps_world_positions_to_cells_from_the_top() {
+        let grid = grid();
+        assert_eq!(grid.cell_at(Vec2::new(101., 101.)), Some((0, 4)));
+        assert_eq!(grid.cell_at(Vec2::new(100. + 16. * 9.5, 100. + 16. * 4.5)), Some((9, 0)));
+        assert_eq!(grid.cell_at(Vec2::new(99., 101.)), None);
+        assert_eq!(grid.cell_at(Vec2::new(101., 100. + 16. * 5.)), None);
+    }
+
+    #[test]
+    fn it_reveals_cells_around_a_position_once() {
+        let grid = grid();
+        let mut fog = FogOfWar::default();
+        assert!(fog.reveal("level", &grid, Vec2::new(108., 108.), 16.));
+        assert!(fog.is_revealed("level", grid.index((0, 4))));
+        assert!(fog.is_revealed("level", grid.index((1, 4))));
+        assert!(!fog.is_revealed("level", grid.index((2, 4))));
+        assert!(!fog.reveal("level", &grid, Vec2::new(108., 108.), 16.));
+    }
+
+    #[test]
+    fn it_encodes_and_decodes_revealed_cells() {
+        let cells = vec![true, false, false, true, true, true, false];
+        assert_eq!(decode_cells(&encode_cells(&cells)), cells);
+    }
+}
@@ -2,30 +2,40 @@ use bevy::prelude::{Component, KeyCode, Query, Res, With};
 use bevy::input::Input;
 use bevy::math::Vec2;
 use bevy_rapier2d::dynamics::Velocity;
+use crate::boost::Boost;
+use crate::level::Health;
+use crate::life_support::LifeSupport;
+use crate::settings::{KeyBindings, Settings};
 use crate::ship::Ship;
+use crate::status_effects::StatusEffects;
 
+#[allow(clippy::type_complexity)]
 pub fn movement_input(
-    mut player_q: Query<(&mut Velocity, &Speed), With<Ship>>,
+    mut player_q: Query<(&mut Velocity, &Speed, Option<&Boost>, Option<(&LifeSupport, &Health)>, Option<&StatusEffects>), With<Ship>>,
     keyboard_input: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
 ) {
-    for (mut velocity, speed) in player_q.iter_mut() {
+    for (mut velocity, speed, boost, life_support, status) in player_q.iter_mut() {
         let mut direction = Vec2::default();
-        handle_keyboard_input(&keyboard_input, &mut direction);
-        velocity.linvel = direction.normalize_or_zero() * speed.0;
+        handle_keyboard_input(&keyboard_input, &settings.bindings, &mut direction);
+        let multiplier = boost.map_or(1., |boost| boost.speed_multiplier())
+            * life_support.map_or(1., |(life_support, health)| life_support.speed_multiplier(health))
+            * status.map_or(1., |status| status.speed_multiplier());
+        velocity.linvel = direction.normalize_or_zero() * speed.0 * multiplier;
     };
 }
 
-fn handle_keyboard_input(keyboard_input: &Res<Input<KeyCode>>, direction: &mut Vec2) {
-    if keyboard_input.pressed(KeyCode::W) {
+fn handle_keyboard_input(keyboard_input: &Res<Input<KeyCode>>, bindings: &KeyBindings, direction: &mut Vec2) {
+    if keyboard_input.pressed(bindings.move_up) {
         direction.y += 1.0;
     }
-    if keyboard_input.pressed(KeyCode::S) {
+    if keyboard_input.pressed(bindings.move_down) {
         direction.y -= 1.0;
     }
-    if keyboard_input.pressed(KeyCode::A) {
+    if keyboard_input.pressed(bindings.move_left) {
         direction.x -= 1.0;
     }
-    if keyboard_input.pressed(KeyCode::D) {
+    if keyboard_input.pressed(bindings.move_right) {
         direction.x += 1.0;
     }
 }
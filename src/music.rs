@@ -0,0 +1,176 @@
+use std::time::Duration;
+use bevy::prelude::{AssetServer, Assets, Handle, Query, Res, ResMut, Resource, State, With};
+use bevy_kira_audio::prelude::*;
+use crate::aggro::Aggro;
+use crate::AppState;
+use crate::audio::{MusicChannel, SoundLibrary};
+use crate::level::Health;
+use crate::settings::Settings;
+use crate::ship::Ship;
+
+const CROSSFADE: Duration = Duration::from_millis(1500);
+const LAYER_FADE: Duration = Duration::from_millis(800);
+const VOLUME_FADE: Duration = Duration::from_millis(100);
+/// Fraction of max health below which the danger layer comes in.
+const DANGER_HEALTH: f32 = 0.3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum MusicTrack {
+    Menu,
+    Intro,
+    InGame,
+    GameOver,
+    PursuitLayer,
+    DangerLayer,
+}
+
+impl MusicTrack {
+    pub const ALL: [MusicTrack; 6] = [
+        MusicTrack::Menu,
+        MusicTrack::Intro,
+        MusicTrack::InGame,
+        MusicTrack::GameOver,
+        MusicTrack::PursuitLayer,
+        MusicTrack::DangerLayer,
+    ];
+
+    /// Layers that play in sync with the in-game track and fade in with intensity.
+    pub const LAYERS: [MusicTrack; 2] = [MusicTrack::PursuitLayer, MusicTrack::DangerLayer];
+
+    pub fn path(&self) -> &'static str {
+        match self {
+            MusicTrack::Menu => "music/menu.mp3",
+            MusicTrack::Intro => "music/intro.mp3",
+            MusicTrack::InGame => "music/in-game.mp3",
+            MusicTrack::GameOver => "music/game-over.mp3",
+            MusicTrack::PursuitLayer => "music/pursuit-layer.mp3",
+            MusicTrack::DangerLayer => "music/danger-layer.mp3",
+        }
+    }
+
+    pub fn for_state(state: &AppState) -> MusicTrack {
+        match state {
+            AppState::Loading | AppState::MainMenu => MusicTrack::Menu,
+            AppState::Intro => MusicTrack::Intro,
+            AppState::InGame => MusicTrack::InGame,
+            AppState::GameOver => MusicTrack::GameOver,
+        }
+    }
+}
+
+struct PlayingTrack {
+    track: MusicTrack,
+    instance: Handle<AudioInstance>,
+    volume: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct MusicPlayer {
+    current: Option<PlayingTrack>,
+    layers: Vec<PlayingTrack>,
+}
+
+/// How loud each intensity layer should be, from 0 (silent) to 1.
+pub fn layer_weight(layer: MusicTrack, chasers: usize, health: Option<&Health>) -> f32 {
+    let active = match layer {
+        MusicTrack::PursuitLayer => chasers > 0,
+        MusicTrack::DangerLayer => health.is_some_and(|health| {
+            health.max > 0. && health.current / health.max < DANGER_HEALTH
+        }),
+        _ => false,
+    };
+    if active { 1. } else { 0. }
+}
+
+pub fn play_state_music(
+    state: Res<State<AppState>>,
+    asset_server: Res<AssetServer>,
+    library: Res<SoundLibrary>,
+    settings: Res<Settings>,
+    music: Res<AudioChannel<MusicChannel>>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    mut player: ResMut<MusicPlayer>,
+) {
+    let wanted = MusicTrack::for_state(&state.0);
+    if player.current.as_ref().is_some_and(|playing| playing.track == wanted) { return; }
+    let stopping = player.current.take().into_iter().chain(player.layers.drain(..));
+    for playing in stopping {
+        if let Some(instance) = instances.get_mut(&playing.instance) {
+            instance.stop(AudioTween::linear(CROSSFADE));
+        }
+    }
+    let Some(source) = library.music(wanted, &asset_server) else { return; };
+    let volume = settings.music_level();
+    player.current = Some(PlayingTrack {
+        track: wanted,
+        instance: music.play(source).looped().with_volume(volume as f64).linear_fade_in(CROSSFADE).handle(),
+        volume,
+    });
+    if wanted != MusicTrack::InGame { return; }
+    for layer in MusicTrack::LAYERS {
+        let Some(source) = library.music(layer, &asset_server) else { continue; };
+        player.layers.push(PlayingTrack {
+            track: layer,
+            instance: music.play(source).looped().with_volume(0.).handle(),
+            volume: 0.,
+        });
+    }
+}
+
+pub fn music_intensity(
+    settings: Res<Settings>,
+    aggro_q: Query<(), With<Aggro>>,
+    health_q: Query<&Health, With<Ship>>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    mut player: ResMut<MusicPlayer>,
+) {
+    let chasers = aggro_q.iter().count();
+    let health = health_q.iter().next();
+    let level = settings.music_level();
+    let player = &mut *player;
+    let base = player.current.iter_mut().map(|playing| (playing, 1., VOLUME_FADE));
+    let layers = player.layers.iter_mut().map(|playing| {
+        let weight = layer_weight(playing.track, chasers, health);
+        (playing, weight, LAYER_FADE)
+    });
+    for (playing, weight, fade) in base.chain(layers) {
+        let volume = level * weight;
+        if volume == playing.volume { continue; }
+        if let Some(instance) = instances.get_mut(&playing.instance) {
+            instance.set_volume(volume as f64, AudioTween::linear(fade));
+            playing.volume = volume;
+        }
+    }
+}
+
+#[cfg(test)]
+mod music_tests {
+    use super::*;
+
+    #[test]
+    fn it_picks_a_track_per_state() {
+        assert_eq!(MusicTrack::for_state(&AppState::MainMenu), MusicTrack::Menu);
+        assert_eq!(MusicTrack::for_state(&AppState::GameOver), MusicTrack::GameOver);
+    }
+
+    #[test]
+    fn it_adds_the_pursuit_layer_while_chased() {
+        assert_eq!(layer_weight(MusicTrack::PursuitLayer, 0, None), 0.);
+        assert_eq!(layer_weight(MusicTrack::PursuitLayer, 2, None), 1.);
+    }
+
+    #[test]
+    fn it_adds_the_danger_layer_on_low_health() {
+        let healthy = Health::new(10.);
+        let hurt = Health { current: 2., max: 10. };
+        assert_eq!(layer_weight(MusicTrack::DangerLayer, 0, Some(&healthy)), 0.);
+        assert_eq!(layer_weight(MusicTrack::DangerLayer, 0, Some(&hurt)), 1.);
+    }
+
+    #[test]
+    fn it_ships_every_track() {
+        for track in MusicTrack::ALL {
+            assert!(std::path::Path::new("assets").join(track.path()).exists(), "{} is missing", track.path());
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use bevy::prelude::{AssetServer, Assets, Component, DetectChanges, Entity, GlobalTransform, Handle, Query, Ref, RemovedComponents, Res, ResMut, Resource, Time, Timer, TimerMode, Vec2, With};
+use bevy_kira_audio::prelude::*;
+use crate::aggro::Aggro;
+use crate::audio::{SfxChannel, Sound, SoundLibrary};
+use crate::camera::MainCamera;
+use crate::settings::Settings;
+
+/// Fast enough to follow moving emitters without zipper noise.
+const MIX_TWEEN: Duration = Duration::from_millis(50);
+
+#[derive(Component)]
+pub struct SoundEmitter {
+    pub max_distance: f32,
+    /// Plays an idle chirp every time the timer finishes.
+    pub chirp: Option<Timer>,
+    /// Screeches when the entity starts chasing the ship.
+    pub screech: bool,
+    /// Keeps a looping hum playing for as long as the entity exists.
+    pub hum: bool,
+    instances: Vec<Handle<AudioInstance>>,
+}
+
+impl SoundEmitter {
+    pub fn organism(chirp_interval: Duration) -> Self {
+        SoundEmitter {
+            max_distance: 320.,
+            chirp: Some(Timer::new(chirp_interval, TimerMode::Repeating)),
+            screech: true,
+            hum: false,
+            instances: vec![],
+        }
+    }
+
+    pub fn artifact() -> Self {
+        SoundEmitter {
+            max_distance: 240.,
+            chirp: None,
+            screech: false,
+            hum: true,
+            instances: vec![],
+        }
+    }
+}
+
+/// Looping instances by emitter, so they can be stopped once their entity is gone.
+#[derive(Resource, Default)]
+pub struct LoopingEmitters(HashMap<Entity, Handle<AudioInstance>>);
+
+/// Volume (0 to 1) and panning (0 hard left, 1 hard right) of an emitter heard from `listener`.
+pub fn spatial_mix(listener: Vec2, emitter: Vec2, max_distance: f32) -> (f32, f32) {
+    if max_distance <= 0. { return (0., 0.5); }
+    let offset = emitter - listener;
+    let volume = (1. - offset.length() / max_distance).clamp(0., 1.).powi(2);
+    let panning = (0.5 + offset.x / max_distance).clamp(0., 1.);
+    (volume, panning)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn emit_world_sounds(
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    library: Res<SoundLibrary>,
+    settings: Res<Settings>,
+    sfx: Res<AudioChannel<SfxChannel>>,
+    audio_instances: Res<Assets<AudioInstance>>,
+    camera_q: Query<&GlobalTransform, With<MainCamera>>,
+    mut emitter_q: Query<(Entity, &GlobalTransform, &mut SoundEmitter, Option<Ref<Aggro>>)>,
+    mut looping: ResMut<LoopingEmitters>,
+) {
+    let Ok(camera_transform) = camera_q.get_single() else { return; };
+    let listener = camera_transform.translation().truncate();
+    for (entity, emitter_transform, mut emitter, aggro) in emitter_q.iter_mut() {
+        emitter.instances.retain(|instance| {
+            audio_instances.get(instance).is_none_or(|instance| instance.state() != PlaybackState::Stopped)
+        });
+        let (volume, panning) = spatial_mix(listener, emitter_transform.translation().truncate(), emitter.max_distance);
+        let volume = (volume * settings.sfx_level()) as f64;
+        let mut sounds = vec![];
+        if let Some(chirp) = emitter.chirp.as_mut() {
+            if chirp.tick(time.delta()).just_finished() { sounds.push(Sound::OrganismChirp); }
+        }
+        if emitter.screech && aggro.is_some_and(|aggro| aggro.is_added()) {
+            sounds.push(Sound::AggroScreech);
+        }
+        for sound in sounds {
+            let Some(source) = library.sound(sound, &asset_server) else { continue; };
+            let instance = sfx.play(source).with_volume(volume).with_panning(panning as f64).handle();
+            emitter.instances.push(instance);
+        }
+        if emitter.hum && !looping.0.contains_key(&entity) {
+            let Some(source) = library.sound(Sound::ArtifactHum, &asset_server) else { continue; };
+            let instance = sfx.play(source).looped().with_volume(volume).with_panning(panning as f64).handle();
+            looping.0.insert(entity, instance.clone());
+            emitter.instances.push(instance);
+        }
+    }
+}
+
+pub fn spatialize_emitters(
+    settings: Res<Settings>,
+    camera_q: Query<&GlobalTransform, With<MainCamera>>,
+    emitter_q: Query<(&GlobalTransform, &SoundEmitter)>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    let Ok(camera_transform) = camera_q.get_single() else { return; };
+    let listener = camera_transform.translation().truncate();
+    for (emitter_transform, emitter) in emitter_q.iter() {
+        let (volume, panning) = spatial_mix(listener, emitter_transform.translation().truncate(), emitter.max_distance);
+        for instance in emitter.instances.iter() {
+            let Some(instance) = audio_instances.get_mut(instance) else { continue; };
+            instance.set_volume((volume * settings.sfx_level()) as f64, AudioTween::linear(MIX_TWEEN));
+            instance.set_panning(panning as f64, AudioTween::linear(MIX_TWEEN));
+        }
+    }
+}
+
+pub fn stop_removed_emitters(
+    mut removed: RemovedComponents<SoundEmitter>,
+    mut looping: ResMut<LoopingEmitters>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    for entity in removed.iter() {
+        let Some(handle) = looping.0.remove(&entity) else { continue; };
+        if let Some(instance) = audio_instances.get_mut(&handle) {
+            instance.stop(AudioTween::linear(MIX_TWEEN));
+        }
+    }
+}
+
+pub fn stop_all_emitters(
+    mut looping: ResMut<LoopingEmitters>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    for (_, handle) in looping.0.drain() {
+        if let Some(instance) = audio_instances.get_mut(&handle) {
+            instance.stop(AudioTween::linear(MIX_TWEEN));
+        }
+    }
+}
+
+#[cfg(test)]
+mod positional_audio_tests {
+    use super::*;
+
+    #[test]
+    fn it_attenuates_with_distance() {
+        let (near, _) = spatial_mix(Vec2::ZERO, Vec2::new(10., 0.), 100.);
+        let (far, _) = spatial_mix(Vec2::ZERO, Vec2::new(80., 0.), 100.);
+        let (silent, _) = spatial_mix(Vec2::ZERO, Vec2::new(0., 150.), 100.);
+        assert!(near > far && far > 0.);
+        assert_eq!(silent, 0.);
+    }
+
+    #[test]
+    fn it_pans_toward_the_emitter() {
+        assert_eq!(spatial_mix(Vec2::ZERO, Vec2::new(0., 50.), 100.).1, 0.5);
+        assert!(spatial_mix(Vec2::ZERO, Vec2::new(-30., 0.), 100.).1 < 0.5);
+        assert_eq!(spatial_mix(Vec2::ZERO, Vec2::new(500., 0.), 100.).1, 1.);
+    }
+}
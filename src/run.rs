@@ -0,0 +1,177 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::{LdtkAsset, LdtkWorldBundle, LevelSelection};
+use crate::beams::UnderBeamItems;
+use crate::camera::LevelBounds;
+use crate::effects::HitStop;
+use crate::minimap::Minimap;
+use crate::scanner::ScannerPing;
+use crate::ship::Ship;
+use crate::stats::RunStats;
+use crate::storage;
+
+pub const FIRST_LEVEL: usize = 0;
+
+/// How the next run starts: from the first level, or back on the level the last run ended on.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RunStart {
+    #[default]
+    NewGame,
+    RetryLevel,
+}
+
+/// Scrambles the start time so runs started close together still get very different seeds.
+pub fn new_seed(time: u64) -> u64 {
+    let mut seed = time.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    seed = (seed ^ (seed >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    seed = (seed ^ (seed >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    seed ^ (seed >> 31)
+}
+
+pub fn spawn_world(commands: &mut Commands, asset_server: &AssetServer) {
+    commands.spawn(LdtkWorldBundle {
+        ldtk_handle: asset_server.load("level.ldtk"),
+        ..Default::default()
+    });
+}
+
+/// Throws away everything the previous run left behind and loads the level from scratch,
+/// so the new ship spawns with full health and an empty inventory.
+#[allow(clippy::too_many_arguments)]
+pub fn start_new_run(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    world_q: Query<Entity, With<Handle<LdtkAsset>>>,
+    ship_q: Query<Entity, With<Ship>>,
+    ping_q: Query<Entity, With<ScannerPing>>,
+    mut time: ResMut<Time>,
+    mut under_beam: ResMut<UnderBeamItems>,
+    mut level_bounds: ResMut<LevelBounds>,
+    mut hit_stop: ResMut<HitStop>,
+    mut minimap: ResMut<Minimap>,
+    mut stats: ResMut<RunStats>,
+    mut run_start: ResMut<RunStart>,
+) {
+    let (level, seed) = match *run_start {
+        RunStart::NewGame => (FIRST_LEVEL, new_seed(storage::unix_time())),
+        RunStart::RetryLevel => (stats.level, stats.seed),
+    };
+    *run_start = RunStart::NewGame;
+    for ship in ship_q.iter() {
+        commands.entity(ship).remove_parent();
+        commands.entity(ship).despawn_recursive();
+    }
+    for entity in world_q.iter().chain(ping_q.iter()) {
+        commands.entity(entity).despawn_recursive();
+    }
+    under_beam.0.clear();
+    level_bounds.0 = None;
+    hit_stop.0 = None;
+    time.set_relative_speed(1.);
+    minimap.reset();
+    *stats = RunStats::new(level, seed);
+    spawn_world(&mut commands, &asset_server);
+    commands.insert_resource(LevelSelection::Index(level));
+}
+
+#[cfg(test)]
+mod run_tests {
+    use std::time::Duration;
+    use bevy::asset::AssetPlugin;
+    use bevy_ecs_ldtk::EntityInstance;
+    use crate::AppState;
+    use crate::damage::{handle_damage, DamageSource, DamageTakenEvent};
+    use crate::game_assets::GameAssets;
+    use crate::hazards::HazardDisc;
+    use crate::level::{spawn_entity_instances, Damage, Health, Inventory, Item, ItemKind};
+    use super::*;
+
+    fn headless_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugin(AssetPlugin::default());
+        app.add_asset::<Image>();
+        app.add_asset::<TextureAtlas>();
+        app.add_state::<AppState>();
+        app.add_event::<DamageTakenEvent>();
+        app.insert_resource(UnderBeamItems(vec![]));
+        app.init_resource::<LevelBounds>();
+        app.init_resource::<HitStop>();
+        app.init_resource::<Minimap>();
+        app.init_resource::<RunStats>();
+        app.init_resource::<RunStart>();
+        app.init_resource::<HazardDisc>();
+        app.init_resource::<GameAssets>();
+        app.add_system(spawn_entity_instances);
+        app.add_system(handle_damage.in_set(OnUpdate(AppState::InGame)));
+        app.add_system(start_new_run.in_schedule(OnEnter(AppState::InGame)));
+        app
+    }
+
+    fn spawn_player_instance(app: &mut App) {
+        app.world.spawn((
+            EntityInstance { identifier: "Player".to_string(), ..default() },
+            TransformBundle::default(),
+        ));
+        app.update();
+    }
+
+    fn go_to(app: &mut App, state: AppState) {
+        app.world.resource_mut::<NextState<AppState>>().set(state);
+        app.update();
+    }
+
+    fn only_ship(app: &mut App) -> (Health, usize) {
+        let mut ship_q = app.world.query_filtered::<(&Health, &Inventory), With<Ship>>();
+        let ships: Vec<(Health, usize)> = ship_q.iter(&app.world)
+            .map(|(health, inventory)| (Health { current: health.current, max: health.max }, inventory.iter().count()))
+            .collect();
+        assert_eq!(ships.len(), 1);
+        ships.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn it_restarts_with_a_fresh_ship_after_dying() {
+        let mut app = headless_app();
+        go_to(&mut app, AppState::Intro);
+        go_to(&mut app, AppState::InGame);
+        spawn_player_instance(&mut app);
+
+        let mut ship_q = app.world.query_filtered::<(Entity, &mut Inventory), With<Ship>>();
+        let (ship, mut inventory) = ship_q.single_mut(&mut app.world);
+        inventory.add(&Item { kind: ItemKind::Gold, description: String::new(), texture: Handle::default() });
+        app.world.entity_mut(ship).insert(Damage(100., Timer::new(Duration::from_nanos(1), TimerMode::Repeating), DamageSource::Organism));
+        for _ in 0..5 { app.update(); }
+        assert_eq!(app.world.resource::<State<AppState>>().0, AppState::GameOver);
+        assert!(only_ship(&mut app).0.current <= 0.);
+
+        go_to(&mut app, AppState::Intro);
+        go_to(&mut app, AppState::InGame);
+        spawn_player_instance(&mut app);
+
+        let (health, items) = only_ship(&mut app);
+        assert_eq!(health.current, health.max);
+        assert_eq!(items, 0);
+        assert!(matches!(*app.world.resource::<LevelSelection>(), LevelSelection::Index(FIRST_LEVEL)));
+    }
+
+    #[test]
+    fn it_retries_the_level_the_run_ended_on() {
+        let mut app = headless_app();
+        go_to(&mut app, AppState::Intro);
+        go_to(&mut app, AppState::InGame);
+        spawn_player_instance(&mut app);
+        app.world.resource_mut::<RunStats>().level = 2;
+        let seed = app.world.resource::<RunStats>().seed;
+        go_to(&mut app, AppState::GameOver);
+
+        *app.world.resource_mut::<RunStart>() = RunStart::RetryLevel;
+        go_to(&mut app, AppState::InGame);
+        spawn_player_instance(&mut app);
+
+        only_ship(&mut app);
+        assert!(matches!(*app.world.resource::<LevelSelection>(), LevelSelection::Index(2)));
+        assert_eq!(app.world.resource::<RunStats>().level, 2);
+        assert_eq!(app.world.resource::<RunStats>().seed, seed);
+        assert_eq!(*app.world.resource::<RunStart>(), RunStart::NewGame);
+    }
+}
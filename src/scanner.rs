@@ -0,0 +1,242 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy_rapier2d::geometry::Collider;
+use crate::aggro::Aggro;
+use crate::beams::UnderBeamItems;
+use crate::indicators::IndicatorTarget;
+use crate::level::{AggroRange, Item, ResourceNameplate};
+use crate::settings::Settings;
+use crate::ship::Ship;
+
+const RING_TEXTURE_SIZE: u32 = 128;
+const RING_THICKNESS: f32 = 3.;
+
+#[derive(Component)]
+pub struct Scanner {
+    pub cooldown: Timer,
+    pub max_radius: f32,
+    pub speed: f32,
+    pub reveal_duration: Duration,
+    pub highlight: Color,
+}
+
+impl Default for Scanner {
+    fn default() -> Self {
+        let mut cooldown = Timer::new(Duration::from_secs(8), TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        Scanner {
+            cooldown,
+            max_radius: 320.,
+            speed: 400.,
+            reveal_duration: Duration::from_secs(4),
+            highlight: Color::hex("#9FF4FF").unwrap(),
+        }
+    }
+}
+
+impl Scanner {
+    /// Starts the cooldown and returns true if the scanner was ready to ping.
+    pub fn try_ping(&mut self) -> bool {
+        if !self.cooldown.finished() { return false; }
+        self.cooldown.reset();
+        true
+    }
+}
+
+#[derive(Component)]
+pub struct ScannerPing {
+    pub origin: Vec2,
+    pub radius: f32,
+    pub max_radius: f32,
+    pub speed: f32,
+    pub reveal_duration: Duration,
+    pub highlight: Color,
+}
+
+impl ScannerPing {
+    /// Grows the ring and returns the band `(from, to)` it swept this frame.
+    pub fn expand(&mut self, delta_seconds: f32) -> (f32, f32) {
+        let from = self.radius;
+        self.radius = (self.radius + self.speed * delta_seconds).min(self.max_radius);
+        (from, self.radius)
+    }
+
+    pub fn finished(&self) -> bool {
+        self.radius >= self.max_radius
+    }
+}
+
+/// Whether a ring sweeping from `from` to `to` passed over something `distance` away.
+pub fn ring_sweeps(from: f32, to: f32, distance: f32) -> bool {
+    distance >= from && distance < to
+}
+
+/// Nameplate shown and sprite tinted until the timer runs out.
+#[derive(Component)]
+pub struct Scanned(pub Timer);
+
+#[derive(Resource)]
+pub struct ScannerRing(pub Handle<Image>);
+
+impl FromWorld for ScannerRing {
+    fn from_world(world: &mut World) -> Self {
+        let mut images = world.resource_mut::<Assets<Image>>();
+        ScannerRing(images.add(ring_image(RING_TEXTURE_SIZE)))
+    }
+}
+
+fn ring_image(size: u32) -> Image {
+    let center = size as f32 / 2.;
+    let data = (0..size * size)
+        .flat_map(|index| {
+            let position = Vec2::new((index % size) as f32 + 0.5, (index / size) as f32 + 0.5);
+            let distance = position.distance(Vec2::splat(center));
+            let alpha = (1. - (center - RING_THICKNESS - distance).abs() / RING_THICKNESS).clamp(0., 1.);
+            [255, 255, 255, (alpha * 255.) as u8]
+        })
+        .collect();
+    Image::new(
+        Extent3d { width: size, height: size, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+pub fn scanner_input(
+    mut commands: Commands,
+    time: Res<Time>,
+    key_input: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    ring: Res<ScannerRing>,
+    mut ship_q: Query<(&GlobalTransform, &mut Scanner), With<Ship>>,
+) {
+    for (ship_transform, mut scanner) in ship_q.iter_mut() {
+        scanner.cooldown.tick(time.delta());
+        if !key_input.just_pressed(settings.bindings.scanner) || !scanner.try_ping() { continue; }
+        let origin = ship_transform.translation().truncate();
+        commands.spawn((
+            ScannerPing {
+                origin,
+                radius: 0.,
+                max_radius: scanner.max_radius,
+                speed: scanner.speed,
+                reveal_duration: scanner.reveal_duration,
+                highlight: scanner.highlight,
+            },
+            SpriteBundle {
+                texture: ring.0.clone(),
+                sprite: Sprite {
+                    color: scanner.highlight,
+                    custom_size: Some(Vec2::ZERO),
+                    ..default()
+                },
+                transform: Transform::from_translation(origin.extend(50.)),
+                ..default()
+            },
+        ));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn scanner_ping(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut ping_q: Query<(Entity, &mut ScannerPing, &mut Sprite)>,
+    ship_q: Query<Entity, With<Ship>>,
+    mut item_q: Query<(Entity, &GlobalTransform, &Children, &mut TextureAtlasSprite, Option<&mut IndicatorTarget>), With<Item>>,
+    mut nameplate_q: Query<&mut Visibility, With<ResourceNameplate>>,
+    aggro_range_q: Query<(&Parent, &GlobalTransform, &Collider), With<AggroRange>>,
+) {
+    let Ok(ship) = ship_q.get_single() else { return; };
+    for (ping_entity, mut ping, mut ring_sprite) in ping_q.iter_mut() {
+        let (from, to) = ping.expand(time.delta_seconds());
+        ring_sprite.custom_size = Some(Vec2::splat(to * 2.));
+        ring_sprite.color.set_a(1. - to / ping.max_radius);
+        for (item, item_transform, children, mut sprite, target) in item_q.iter_mut() {
+            let distance = ping.origin.distance(item_transform.translation().truncate());
+            if !ring_sweeps(from, to, distance) { continue; }
+            sprite.color = ping.highlight;
+            for child in children.iter() {
+                if let Ok(mut visibility) = nameplate_q.get_mut(*child) {
+                    *visibility = Visibility::Visible;
+                }
+            }
+            if let Some(mut target) = target {
+                target.discovered = true;
+            }
+            commands.entity(item).insert(Scanned(Timer::new(ping.reveal_duration, TimerMode::Once)));
+        }
+        for (organism, range_transform, range_collider) in aggro_range_q.iter() {
+            let Some(ball) = range_collider.as_ball() else { continue; };
+            let distance = ping.origin.distance(range_transform.translation().truncate());
+            if ring_sweeps(from, to, (distance - ball.radius()).max(0.)) {
+                commands.entity(organism.get()).insert(Aggro(ship));
+            }
+        }
+        if ping.finished() {
+            commands.entity(ping_entity).despawn();
+        }
+    }
+}
+
+pub fn expire_scanned(
+    mut commands: Commands,
+    time: Res<Time>,
+    under_beam: Res<UnderBeamItems>,
+    mut scanned_q: Query<(Entity, &mut Scanned, &Children, &mut TextureAtlasSprite)>,
+    mut nameplate_q: Query<&mut Visibility, With<ResourceNameplate>>,
+) {
+    for (item, mut scanned, children, mut sprite) in scanned_q.iter_mut() {
+        scanned.0.tick(time.delta());
+        if !scanned.0.finished() { continue; }
+        sprite.color = Color::WHITE;
+        if !under_beam.0.contains(&item) {
+            for child in children.iter() {
+                if let Ok(mut visibility) = nameplate_q.get_mut(*child) {
+                    *visibility = Visibility::Hidden;
+                }
+            }
+        }
+        commands.entity(item).remove::<Scanned>();
+    }
+}
+
+#[cfg(test)]
+mod scanner_tests {
+    use super::*;
+
+    #[test]
+    fn it_pings_only_when_cooled_down() {
+        let mut scanner = Scanner::default();
+        assert!(scanner.try_ping());
+        assert!(!scanner.try_ping());
+        scanner.cooldown.tick(scanner.cooldown.duration());
+        assert!(scanner.try_ping());
+    }
+
+    #[test]
+    fn it_expands_the_ring_up_to_its_max_radius() {
+        let mut ping = ScannerPing {
+            origin: Vec2::ZERO,
+            radius: 0.,
+            max_radius: 100.,
+            speed: 400.,
+            reveal_duration: Duration::from_secs(1),
+            highlight: Color::WHITE,
+        };
+        assert_eq!(ping.expand(0.1), (0., 40.));
+        assert!(!ping.finished());
+        assert_eq!(ping.expand(1.), (40., 100.));
+        assert!(ping.finished());
+    }
+
+    #[test]
+    fn it_sweeps_each_distance_once() {
+        assert!(ring_sweeps(0., 40., 0.));
+        assert!(ring_sweeps(0., 40., 39.));
+        assert!(!ring_sweeps(0., 40., 40.));
+        assert!(ring_sweeps(40., 80., 40.));
+    }
+}
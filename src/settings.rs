@@ -0,0 +1,302 @@
+use bevy::prelude::{DetectChanges, KeyCode, Query, Res, ResMut, Resource, warn, Window, With};
+use bevy::window::{PresentMode, PrimaryWindow, WindowMode};
+use serde::{Deserialize, Serialize};
+use crate::effects::ScreenShakeSettings;
+use crate::storage;
+
+const SETTINGS_KEY: &str = "settings";
+
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Settings {
+    pub zoom: f32,
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub window_mode: WindowModeSetting,
+    pub vsync: bool,
+    pub camera_shake: bool,
+    pub text_speed: TextSpeed,
+    pub bindings: KeyBindings,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum WindowModeSetting {
+    #[default]
+    Windowed,
+    BorderlessFullscreen,
+    Fullscreen,
+}
+
+impl WindowModeSetting {
+    pub fn label(&self) -> &'static str {
+        match self {
+            WindowModeSetting::Windowed => "Windowed",
+            WindowModeSetting::BorderlessFullscreen => "Borderless",
+            WindowModeSetting::Fullscreen => "Fullscreen",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            WindowModeSetting::Windowed => WindowModeSetting::BorderlessFullscreen,
+            WindowModeSetting::BorderlessFullscreen => WindowModeSetting::Fullscreen,
+            WindowModeSetting::Fullscreen => WindowModeSetting::Windowed,
+        }
+    }
+
+    pub fn window_mode(&self) -> WindowMode {
+        match self {
+            WindowModeSetting::Windowed => WindowMode::Windowed,
+            WindowModeSetting::BorderlessFullscreen => WindowMode::BorderlessFullscreen,
+            WindowModeSetting::Fullscreen => WindowMode::Fullscreen,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TextSpeed {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+    Instant,
+}
+
+impl TextSpeed {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TextSpeed::Slow => "Slow",
+            TextSpeed::Normal => "Normal",
+            TextSpeed::Fast => "Fast",
+            TextSpeed::Instant => "Instant",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TextSpeed::Slow => TextSpeed::Normal,
+            TextSpeed::Normal => TextSpeed::Fast,
+            TextSpeed::Fast => TextSpeed::Instant,
+            TextSpeed::Instant => TextSpeed::Slow,
+        }
+    }
+
+    /// Characters revealed per second, or `None` to show text all at once.
+    pub fn chars_per_second(&self) -> Option<f32> {
+        match self {
+            TextSpeed::Slow => Some(20.),
+            TextSpeed::Normal => Some(45.),
+            TextSpeed::Fast => Some(90.),
+            TextSpeed::Instant => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Boost,
+    Scanner,
+    Crafting,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Boost,
+        Action::Scanner,
+        Action::Crafting,
+        Action::Pause,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move Up",
+            Action::MoveDown => "Move Down",
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::Boost => "Boost",
+            Action::Scanner => "Scanner",
+            Action::Crafting => "Crafting",
+            Action::Pause => "Pause",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub move_up: KeyCode,
+    pub move_down: KeyCode,
+    pub move_left: KeyCode,
+    pub move_right: KeyCode,
+    pub boost: KeyCode,
+    pub scanner: KeyCode,
+    pub crafting: KeyCode,
+    pub pause: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            move_up: KeyCode::W,
+            move_down: KeyCode::S,
+            move_left: KeyCode::A,
+            move_right: KeyCode::D,
+            boost: KeyCode::LShift,
+            scanner: KeyCode::Q,
+            crafting: KeyCode::C,
+            pause: KeyCode::Escape,
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn key(&self, action: Action) -> KeyCode {
+        match action {
+            Action::MoveUp => self.move_up,
+            Action::MoveDown => self.move_down,
+            Action::MoveLeft => self.move_left,
+            Action::MoveRight => self.move_right,
+            Action::Boost => self.boost,
+            Action::Scanner => self.scanner,
+            Action::Crafting => self.crafting,
+            Action::Pause => self.pause,
+        }
+    }
+
+    /// Binds `key` to `action`, swapping with whichever action used it before so no key is bound twice.
+    pub fn rebind(&mut self, action: Action, key: KeyCode) {
+        let previous = self.key(action);
+        if let Some(other) = Action::ALL.into_iter().find(|other| *other != action && self.key(*other) == key) {
+            *self.key_mut(other) = previous;
+        }
+        *self.key_mut(action) = key;
+    }
+
+    fn key_mut(&mut self, action: Action) -> &mut KeyCode {
+        match action {
+            Action::MoveUp => &mut self.move_up,
+            Action::MoveDown => &mut self.move_down,
+            Action::MoveLeft => &mut self.move_left,
+            Action::MoveRight => &mut self.move_right,
+            Action::Boost => &mut self.boost,
+            Action::Scanner => &mut self.scanner,
+            Action::Crafting => &mut self.crafting,
+            Action::Pause => &mut self.pause,
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            zoom: 0.55,
+            master_volume: 1.,
+            music_volume: 0.6,
+            sfx_volume: 0.8,
+            window_mode: WindowModeSetting::default(),
+            vsync: true,
+            camera_shake: true,
+            text_speed: TextSpeed::default(),
+            bindings: KeyBindings::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn from_ron(text: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(text)
+    }
+
+    pub fn music_level(&self) -> f32 {
+        (self.master_volume * self.music_volume).clamp(0., 1.)
+    }
+
+    pub fn sfx_level(&self) -> f32 {
+        (self.master_volume * self.sfx_volume).clamp(0., 1.)
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+}
+
+pub fn load_settings() -> Settings {
+    let Some(text) = storage::read(SETTINGS_KEY) else { return Settings::default(); };
+    Settings::from_ron(&text).unwrap_or_else(|error| {
+        warn!("Ignoring malformed settings: {}", error);
+        Settings::default()
+    })
+}
+
+pub fn save_settings(settings: Res<Settings>) {
+    if !settings.is_changed() || settings.is_added() { return; }
+    match settings.to_ron() {
+        Ok(text) => storage::write(SETTINGS_KEY, &text),
+        Err(error) => warn!("Could not serialize settings: {}", error),
+    }
+}
+
+pub fn apply_window_settings(settings: Res<Settings>, mut window_q: Query<&mut Window, With<PrimaryWindow>>) {
+    if !settings.is_changed() { return; }
+    for mut window in window_q.iter_mut() {
+        let mode = settings.window_mode.window_mode();
+        if window.mode != mode { window.mode = mode; }
+        let present_mode = if settings.vsync { PresentMode::AutoVsync } else { PresentMode::AutoNoVsync };
+        if window.present_mode != present_mode { window.present_mode = present_mode; }
+    }
+}
+
+pub fn apply_gameplay_settings(settings: Res<Settings>, mut shake_settings: ResMut<ScreenShakeSettings>) {
+    if !settings.is_changed() { return; }
+    shake_settings.enabled = settings.camera_shake;
+}
+
+#[cfg(test)]
+mod settings_tests {
+    use super::*;
+
+    #[test]
+    fn it_round_trips_through_ron() {
+        let settings = Settings { zoom: 0.8, music_volume: 0.2, ..Settings::default() };
+        let text = settings.to_ron().unwrap();
+        assert_eq!(Settings::from_ron(&text).unwrap(), settings);
+    }
+
+    #[test]
+    fn it_fills_missing_fields_with_defaults() {
+        assert_eq!(Settings::from_ron("()").unwrap(), Settings::default());
+    }
+
+    #[test]
+    fn it_round_trips_key_bindings() {
+        let mut settings = Settings::default();
+        settings.bindings.rebind(Action::Scanner, KeyCode::E);
+        let text = settings.to_ron().unwrap();
+        assert_eq!(Settings::from_ron(&text).unwrap().bindings.scanner, KeyCode::E);
+    }
+
+    #[test]
+    fn it_swaps_bindings_instead_of_binding_a_key_twice() {
+        let mut bindings = KeyBindings::default();
+        bindings.rebind(Action::MoveUp, KeyCode::S);
+        assert_eq!(bindings.move_up, KeyCode::S);
+        assert_eq!(bindings.move_down, KeyCode::W);
+    }
+
+    #[test]
+    fn it_scales_channel_volumes_by_the_master_volume() {
+        let settings = Settings { master_volume: 0.5, music_volume: 0.6, sfx_volume: 2., ..Settings::default() };
+        assert!((settings.music_level() - 0.3).abs() < 1e-6);
+        assert_eq!(settings.sfx_level(), 1.);
+    }
+}
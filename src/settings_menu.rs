@@ -0,0 +1,355 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use bevy_rapier2d::plugin::RapierConfiguration;
+use crate::AppState;
+use crate::settings::{Action, Settings};
+
+const VOLUME_STEP: f32 = 0.1;
+
+/// Screens drawn over whatever `AppState` is active. The game is paused while one is open.
+#[derive(States, Clone, PartialEq, Eq, Debug, Hash, Default)]
+pub enum MenuOverlay {
+    #[default]
+    Closed,
+    Paused,
+    Settings,
+}
+
+#[derive(Component)]
+pub struct PauseUI;
+
+#[derive(Component)]
+pub struct SettingsUI;
+
+#[derive(Component)]
+pub struct SettingsPanel;
+
+#[derive(Component)]
+pub struct OpenSettingsButton;
+
+#[derive(Component, Clone, Copy)]
+pub enum PauseControl {
+    Resume,
+    Settings,
+}
+
+#[derive(Clone, Copy)]
+pub enum VolumeChannel {
+    Master,
+    Music,
+    Sfx,
+}
+
+#[derive(Component, Clone, Copy)]
+pub enum SettingsControl {
+    Volume(VolumeChannel, f32),
+    WindowMode,
+    Vsync,
+    CameraShake,
+    TextSpeed,
+    Rebind(Action),
+    Back,
+}
+
+/// The action waiting for its new key while the player rebinds it.
+#[derive(Resource, Default)]
+pub struct AwaitingBinding(pub Option<Action>);
+
+fn volume_mut(settings: &mut Settings, channel: VolumeChannel) -> &mut f32 {
+    match channel {
+        VolumeChannel::Master => &mut settings.master_volume,
+        VolumeChannel::Music => &mut settings.music_volume,
+        VolumeChannel::Sfx => &mut settings.sfx_volume,
+    }
+}
+
+pub fn adjust_volume(volume: f32, delta: f32) -> f32 {
+    ((volume + delta) * 10.).round().clamp(0., 10.) / 10.
+}
+
+pub fn toggle_pause(
+    key_input: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    awaiting: Res<AwaitingBinding>,
+    overlay: Res<State<MenuOverlay>>,
+    mut next_overlay: ResMut<NextState<MenuOverlay>>,
+) {
+    if awaiting.0.is_some() || !key_input.just_pressed(settings.bindings.pause) { return; }
+    next_overlay.set(match overlay.0 {
+        MenuOverlay::Closed => MenuOverlay::Paused,
+        MenuOverlay::Paused => MenuOverlay::Closed,
+        MenuOverlay::Settings => MenuOverlay::Paused,
+    });
+}
+
+pub fn close_overlay(mut next_overlay: ResMut<NextState<MenuOverlay>>) {
+    next_overlay.set(MenuOverlay::Closed);
+}
+
+pub fn pause_game(mut time: ResMut<Time>, mut rapier: ResMut<RapierConfiguration>) {
+    time.pause();
+    rapier.physics_pipeline_active = false;
+}
+
+pub fn resume_game(mut time: ResMut<Time>, mut rapier: ResMut<RapierConfiguration>) {
+    time.unpause();
+    rapier.physics_pipeline_active = true;
+}
+
+fn spawn_overlay(commands: &mut Commands, marker: impl Component) -> Entity {
+    commands.spawn((marker, NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: Color::rgba(0., 0., 0., 0.6).into(),
+        focus_policy: FocusPolicy::Block,
+        z_index: ZIndex::Global(10),
+        ..default()
+    })).id()
+}
+
+fn spawn_menu_button(parent: &mut ChildBuilder, asset_server: &Res<AssetServer>, control: impl Component, label: &str) {
+    parent.spawn((control, ButtonBundle {
+        style: Style {
+            padding: UiRect::new(Val::Px(8.), Val::Px(8.), Val::Px(2.), Val::Px(2.)),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        background_color: Color::hex("#43374F").unwrap().into(),
+        ..default()
+    })).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            label,
+            TextStyle {
+                font: asset_server.load("fonts/static/JetBrainsMono-Regular.ttf"),
+                font_size: 18.,
+                ..default()
+            }));
+    });
+}
+
+pub fn setup_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let overlay = spawn_overlay(&mut commands, PauseUI);
+    commands.entity(overlay).with_children(|parent| {
+        parent.spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                gap: Size::all(Val::Px(12.)),
+                ..default()
+            },
+            ..default()
+        }).with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Paused",
+                TextStyle {
+                    font: asset_server.load("fonts/JollyLodger-Regular.ttf"),
+                    font_size: 96.,
+                    color: Color::hex("#FFF").unwrap(),
+                },
+            ));
+            spawn_menu_button(parent, &asset_server, PauseControl::Resume, "Resume");
+            spawn_menu_button(parent, &asset_server, PauseControl::Settings, "Settings");
+        });
+    });
+}
+
+pub fn pause_menu_interactions(
+    interaction_q: Query<(&Interaction, &PauseControl), Changed<Interaction>>,
+    mut next_overlay: ResMut<NextState<MenuOverlay>>,
+) {
+    for (interaction, control) in interaction_q.iter() {
+        if *interaction != Interaction::Clicked { continue; }
+        next_overlay.set(match control {
+            PauseControl::Resume => MenuOverlay::Closed,
+            PauseControl::Settings => MenuOverlay::Settings,
+        });
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn open_settings_interactions(
+    mut interaction_q: Query<(&Interaction, &Children), (Changed<Interaction>, With<OpenSettingsButton>)>,
+    mut text_q: Query<&mut Text>,
+    mut next_overlay: ResMut<NextState<MenuOverlay>>,
+) {
+    for (interaction, children) in &mut interaction_q {
+        let Ok(mut text) = text_q.get_mut(children[0]) else { continue; };
+        match *interaction {
+            Interaction::Clicked => next_overlay.set(MenuOverlay::Settings),
+            Interaction::Hovered => text.sections[0].value = "- Settings -".to_string(),
+            Interaction::None => text.sections[0].value = "Settings".to_string(),
+        }
+    }
+}
+
+pub fn setup_settings_menu(mut commands: Commands) {
+    let overlay = spawn_overlay(&mut commands, SettingsUI);
+    commands.entity(overlay).with_children(|parent| {
+        parent.spawn((SettingsPanel, NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(16.)),
+                gap: Size::all(Val::Px(6.)),
+                ..default()
+            },
+            background_color: Color::rgba(27. / 255., 10. / 255., 40. / 255., 0.95).into(),
+            ..default()
+        }));
+    });
+}
+
+pub fn settings_menu_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    awaiting: Res<AwaitingBinding>,
+    panel_q: Query<(Entity, Ref<SettingsPanel>)>,
+) {
+    let Ok((panel, panel_ref)) = panel_q.get_single() else { return; };
+    if !settings.is_changed() && !awaiting.is_changed() && !panel_ref.is_added() { return; }
+    commands.entity(panel).despawn_descendants();
+    commands.entity(panel).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            "Settings",
+            TextStyle {
+                font: asset_server.load("fonts/JollyLodger-Regular.ttf"),
+                font_size: 48.,
+                color: Color::hex("#FFF").unwrap(),
+            },
+        ));
+        let volumes = [
+            ("Master Volume", VolumeChannel::Master, settings.master_volume),
+            ("Music Volume", VolumeChannel::Music, settings.music_volume),
+            ("SFX Volume", VolumeChannel::Sfx, settings.sfx_volume),
+        ];
+        for (label, channel, volume) in volumes {
+            spawn_row(parent, &asset_server, label, |parent| {
+                spawn_menu_button(parent, &asset_server, SettingsControl::Volume(channel, -VOLUME_STEP), "-");
+                spawn_value(parent, &asset_server, &format!("{:.0}%", volume * 100.));
+                spawn_menu_button(parent, &asset_server, SettingsControl::Volume(channel, VOLUME_STEP), "+");
+            });
+        }
+        spawn_row(parent, &asset_server, "Window Mode", |parent| {
+            spawn_menu_button(parent, &asset_server, SettingsControl::WindowMode, settings.window_mode.label());
+        });
+        spawn_row(parent, &asset_server, "VSync", |parent| {
+            spawn_menu_button(parent, &asset_server, SettingsControl::Vsync, on_off(settings.vsync));
+        });
+        spawn_row(parent, &asset_server, "Camera Shake", |parent| {
+            spawn_menu_button(parent, &asset_server, SettingsControl::CameraShake, on_off(settings.camera_shake));
+        });
+        spawn_row(parent, &asset_server, "Text Speed", |parent| {
+            spawn_menu_button(parent, &asset_server, SettingsControl::TextSpeed, settings.text_speed.label());
+        });
+        for action in Action::ALL {
+            let key = if awaiting.0 == Some(action) {
+                "Press a key...".to_string()
+            } else {
+                format!("{:?}", settings.bindings.key(action))
+            };
+            spawn_row(parent, &asset_server, action.label(), |parent| {
+                spawn_menu_button(parent, &asset_server, SettingsControl::Rebind(action), &key);
+            });
+        }
+        spawn_menu_button(parent, &asset_server, SettingsControl::Back, "Back");
+    });
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "On" } else { "Off" }
+}
+
+fn spawn_row(parent: &mut ChildBuilder, asset_server: &Res<AssetServer>, label: &str, controls: impl FnOnce(&mut ChildBuilder)) {
+    parent.spawn(NodeBundle {
+        style: Style {
+            align_items: AlignItems::Center,
+            gap: Size::all(Val::Px(8.)),
+            ..default()
+        },
+        ..default()
+    }).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            label,
+            TextStyle {
+                font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+                font_size: 18.,
+                ..default()
+            }).with_style(Style {
+                size: Size::width(Val::Px(180.)),
+                ..default()
+            }));
+        controls(parent);
+    });
+}
+
+fn spawn_value(parent: &mut ChildBuilder, asset_server: &Res<AssetServer>, value: &str) {
+    parent.spawn(TextBundle::from_section(
+        value,
+        TextStyle {
+            font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+            font_size: 18.,
+            ..default()
+        }));
+}
+
+pub fn settings_menu_interactions(
+    interaction_q: Query<(&Interaction, &SettingsControl), Changed<Interaction>>,
+    app_state: Res<State<AppState>>,
+    mut settings: ResMut<Settings>,
+    mut awaiting: ResMut<AwaitingBinding>,
+    mut next_overlay: ResMut<NextState<MenuOverlay>>,
+) {
+    for (interaction, control) in interaction_q.iter() {
+        if *interaction != Interaction::Clicked || awaiting.0.is_some() { continue; }
+        match *control {
+            SettingsControl::Volume(channel, delta) => {
+                let volume = volume_mut(&mut settings, channel);
+                *volume = adjust_volume(*volume, delta);
+            }
+            SettingsControl::WindowMode => settings.window_mode = settings.window_mode.next(),
+            SettingsControl::Vsync => settings.vsync = !settings.vsync,
+            SettingsControl::CameraShake => settings.camera_shake = !settings.camera_shake,
+            SettingsControl::TextSpeed => settings.text_speed = settings.text_speed.next(),
+            SettingsControl::Rebind(action) => awaiting.0 = Some(action),
+            SettingsControl::Back => next_overlay.set(if app_state.0 == AppState::InGame {
+                MenuOverlay::Paused
+            } else {
+                MenuOverlay::Closed
+            }),
+        }
+    }
+}
+
+pub fn capture_binding(
+    key_input: Res<Input<KeyCode>>,
+    mut awaiting: ResMut<AwaitingBinding>,
+    mut settings: ResMut<Settings>,
+) {
+    let Some(action) = awaiting.0 else { return; };
+    let Some(key) = key_input.get_just_pressed().next() else { return; };
+    if *key != KeyCode::Escape || action == Action::Pause {
+        settings.bindings.rebind(action, *key);
+    }
+    awaiting.0 = None;
+}
+
+pub fn cancel_binding(mut awaiting: ResMut<AwaitingBinding>) {
+    awaiting.0 = None;
+}
+
+#[cfg(test)]
+mod settings_menu_tests {
+    use super::*;
+
+    #[test]
+    fn it_steps_volume_within_range() {
+        assert_eq!(adjust_volume(0.5, VOLUME_STEP), 0.6);
+        assert_eq!(adjust_volume(1., VOLUME_STEP), 1.);
+        assert_eq!(adjust_volume(0.05, -VOLUME_STEP), 0.);
+    }
+}
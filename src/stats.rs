@@ -0,0 +1,148 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+use bevy::prelude::*;
+use crate::aggro::Aggro;
+use crate::beams::BeamUpEvent;
+use crate::damage::{DamageSource, DamageTakenEvent};
+use crate::level::{Item, ItemKind};
+use crate::ship::Ship;
+
+/// Everything that happened during the current run, reset whenever a run starts.
+#[derive(Resource, Default)]
+pub struct RunStats {
+    pub level: usize,
+    /// Identifies the run on the leaderboard; retrying a level keeps it.
+    pub seed: u64,
+    pub time_survived: Duration,
+    pub items_collected: HashMap<ItemKind, u32>,
+    pub damage_taken: HashMap<DamageSource, f32>,
+    pub distance: f32,
+    pub organisms_aggroed: HashSet<Entity>,
+    /// Source of the most recent hit; once the ship is dead, the killing blow.
+    pub last_hit: Option<DamageSource>,
+    last_position: Option<Vec2>,
+}
+
+impl RunStats {
+    pub fn new(level: usize, seed: u64) -> Self {
+        RunStats { level, seed, ..default() }
+    }
+
+    pub fn record_item(&mut self, kind: ItemKind) {
+        *self.items_collected.entry(kind).or_default() += 1;
+    }
+
+    pub fn record_damage(&mut self, amount: f32, source: DamageSource) {
+        *self.damage_taken.entry(source).or_default() += amount;
+        self.last_hit = Some(source);
+    }
+
+    pub fn record_position(&mut self, position: Vec2) {
+        if let Some(last) = self.last_position {
+            self.distance += last.distance(position);
+        }
+        self.last_position = Some(position);
+    }
+
+    pub fn cause_of_death(&self) -> String {
+        match self.last_hit {
+            Some(source) => format!("Killed by {}", source.label()),
+            None => "Lost in space".to_string(),
+        }
+    }
+
+    /// Lines shown on the game over screen.
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Time survived: {}", format_duration(self.time_survived)),
+            format!("Distance travelled: {:.0}", self.distance),
+            format!("Organisms aggroed: {}", self.organisms_aggroed.len()),
+        ];
+        let mut items: BTreeMap<&str, u32> = BTreeMap::new();
+        for (kind, count) in self.items_collected.iter() {
+            *items.entry(kind.name()).or_default() += count;
+        }
+        if items.is_empty() {
+            lines.push("Items collected: none".to_string());
+        } else {
+            let items: Vec<String> = items.iter().map(|(name, count)| format!("{} x{}", name, count)).collect();
+            lines.push(format!("Items collected: {}", items.join(", ")));
+        }
+        let mut damage: BTreeMap<&str, f32> = BTreeMap::new();
+        for (source, amount) in self.damage_taken.iter() {
+            *damage.entry(source.label()).or_default() += amount;
+        }
+        for (label, amount) in damage.into_iter().filter(|(_, amount)| amount.round() > 0.) {
+            lines.push(format!("Damage from {}: {:.0}", label, amount));
+        }
+        lines
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+pub fn track_run_stats(
+    time: Res<Time>,
+    mut stats: ResMut<RunStats>,
+    mut beam_up_events: EventReader<BeamUpEvent>,
+    mut damage_events: EventReader<DamageTakenEvent>,
+    item_q: Query<&Item>,
+    ship_q: Query<&GlobalTransform, With<Ship>>,
+    aggro_q: Query<Entity, Added<Aggro>>,
+) {
+    stats.time_survived += time.delta();
+    for ev in beam_up_events.iter() {
+        if let Ok(item) = item_q.get(ev.0) {
+            for _ in 0..ev.1 {
+                stats.record_item(item.kind);
+            }
+        }
+    }
+    for ev in damage_events.iter() {
+        stats.record_damage(ev.0, ev.1);
+    }
+    if let Ok(ship_transform) = ship_q.get_single() {
+        stats.record_position(ship_transform.translation().truncate());
+    }
+    stats.organisms_aggroed.extend(aggro_q.iter());
+}
+
+#[cfg(test)]
+mod stats_tests {
+    use super::*;
+
+    #[test]
+    fn it_accumulates_distance_between_positions() {
+        let mut stats = RunStats::default();
+        stats.record_position(Vec2::ZERO);
+        stats.record_position(Vec2::new(3., 4.));
+        stats.record_position(Vec2::new(3., 10.));
+        assert_eq!(stats.distance, 11.);
+    }
+
+    #[test]
+    fn it_blames_the_last_hit_for_the_death() {
+        let mut stats = RunStats::default();
+        assert_eq!(stats.cause_of_death(), "Lost in space");
+        stats.record_damage(1., DamageSource::Organism);
+        stats.record_damage(2., DamageSource::HostileOrganism);
+        stats.record_damage(1., DamageSource::Organism);
+        assert_eq!(stats.cause_of_death(), "Killed by Life Form");
+        assert_eq!(stats.damage_taken[&DamageSource::Organism], 2.);
+    }
+
+    #[test]
+    fn it_summarizes_items_by_name() {
+        let mut stats = RunStats { time_survived: Duration::from_secs(75), ..default() };
+        stats.record_item(ItemKind::Organism);
+        stats.record_item(ItemKind::Organism);
+        stats.record_item(ItemKind::YellowOrganism);
+        stats.record_item(ItemKind::Gold);
+        let summary = stats.summary();
+        assert_eq!(summary[0], "Time survived: 1:15");
+        assert!(summary.contains(&"Items collected: Gold x1, Hostile Organism x1, Organism x2".to_string()));
+    }
+}
@@ -0,0 +1,393 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy_rapier2d::pipeline::CollisionEvent;
+use crate::AppState;
+use crate::crafting::ArtifactActivatedEvent;
+use crate::damage::{DamageSource, DamageTakenEvent};
+use crate::level::{Health, ItemKind};
+use crate::life_support::restore_amount;
+use crate::ship::Ship;
+use crate::ui::InGameUI;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum StatusKind {
+    Poison,
+    Slow,
+    Radiation,
+    Regen,
+}
+
+impl StatusKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StatusKind::Poison => "PSN",
+            StatusKind::Slow => "SLW",
+            StatusKind::Radiation => "RAD",
+            StatusKind::Regen => "RGN",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            StatusKind::Poison => Color::hex("#7ED957").unwrap(),
+            StatusKind::Slow => Color::hex("#8FD3FF").unwrap(),
+            StatusKind::Radiation => Color::hex("#F2D16B").unwrap(),
+            StatusKind::Regen => Color::hex("#FF8AC8").unwrap(),
+        }
+    }
+
+    /// What a tick of this effect counts as in the run stats, if it hurts.
+    pub fn damage_source(&self) -> Option<DamageSource> {
+        match self {
+            StatusKind::Poison => Some(DamageSource::Poison),
+            StatusKind::Radiation => Some(DamageSource::Radiation),
+            StatusKind::Slow | StatusKind::Regen => None,
+        }
+    }
+}
+
+/// What happens when an effect is applied while one of the same kind is already active.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Stacking {
+    /// Restart the existing effect, keeping the stronger magnitude.
+    Refresh,
+    /// Add the new duration on top of what's left.
+    Extend,
+    /// Run side by side up to `max` copies; past that, the copy closest to expiring restarts.
+    Stack { max: usize },
+}
+
+#[derive(Clone, Debug)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub duration: Timer,
+    pub tick: Timer,
+    /// Health per tick for poison, radiation and regen; fraction of speed lost for slow.
+    pub magnitude: f32,
+    pub stacking: Stacking,
+}
+
+impl StatusEffect {
+    pub fn new(kind: StatusKind, duration: Duration, tick_interval: Duration, magnitude: f32, stacking: Stacking) -> Self {
+        StatusEffect {
+            kind,
+            duration: Timer::new(duration, TimerMode::Once),
+            tick: Timer::new(tick_interval, TimerMode::Repeating),
+            magnitude,
+            stacking,
+        }
+    }
+
+    pub fn poison(damage_per_second: f32, duration: Duration) -> Self {
+        StatusEffect::new(StatusKind::Poison, duration, Duration::from_secs(1), damage_per_second, Stacking::Stack { max: 3 })
+    }
+
+    pub fn slow(fraction: f32, duration: Duration) -> Self {
+        StatusEffect::new(StatusKind::Slow, duration, duration, fraction.clamp(0., 1.), Stacking::Refresh)
+    }
+
+    pub fn radiation(damage_per_second: f32, duration: Duration) -> Self {
+        StatusEffect::new(StatusKind::Radiation, duration, Duration::from_secs(1), damage_per_second, Stacking::Stack { max: 5 })
+    }
+
+    /// Heals `total` evenly over `duration`, half a second at a time.
+    pub fn regen(total: f32, duration: Duration) -> Self {
+        let ticks = (duration.as_secs_f32() * 2.).max(1.);
+        StatusEffect::new(StatusKind::Regen, duration, Duration::from_millis(500), total / ticks, Stacking::Extend)
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.duration.remaining()
+    }
+}
+
+/// A tick of health change produced by an effect.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct StatusTick {
+    pub kind: StatusKind,
+    pub amount: f32,
+}
+
+#[derive(Component, Default)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    pub fn apply(&mut self, effect: StatusEffect) {
+        match effect.stacking {
+            Stacking::Refresh => {
+                let Some(active) = self.effects.iter_mut().find(|active| active.kind == effect.kind) else {
+                    self.effects.push(effect);
+                    return;
+                };
+                let duration = active.duration.duration().max(effect.duration.duration());
+                active.duration = Timer::new(duration, TimerMode::Once);
+                active.magnitude = active.magnitude.max(effect.magnitude);
+            }
+            Stacking::Extend => {
+                let Some(active) = self.effects.iter_mut().find(|active| active.kind == effect.kind) else {
+                    self.effects.push(effect);
+                    return;
+                };
+                let duration = active.duration.duration() + effect.duration.duration();
+                active.duration.set_duration(duration);
+            }
+            Stacking::Stack { max } => {
+                if self.stacks(effect.kind) < max {
+                    self.effects.push(effect);
+                    return;
+                }
+                let Some(oldest) = self.effects.iter_mut()
+                    .filter(|active| active.kind == effect.kind)
+                    .min_by(|a, b| a.remaining().cmp(&b.remaining())) else { return; };
+                *oldest = effect;
+            }
+        }
+    }
+
+    pub fn stacks(&self, kind: StatusKind) -> usize {
+        self.effects.iter().filter(|effect| effect.kind == kind).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Strongest slow wins; slows don't compound.
+    pub fn speed_multiplier(&self) -> f32 {
+        let slow = self.effects.iter()
+            .filter(|effect| effect.kind == StatusKind::Slow)
+            .map(|effect| effect.magnitude)
+            .fold(0., f32::max);
+        1. - slow
+    }
+
+    /// Advances every effect, returning the ticks that fired and dropping expired effects.
+    pub fn tick(&mut self, delta: Duration) -> Vec<StatusTick> {
+        let mut ticks = vec![];
+        for effect in self.effects.iter_mut() {
+            let remaining = effect.remaining();
+            effect.duration.tick(delta);
+            effect.tick.tick(delta.min(remaining));
+            if effect.kind == StatusKind::Slow { continue; }
+            for _ in 0..effect.tick.times_finished_this_tick() {
+                ticks.push(StatusTick { kind: effect.kind, amount: effect.magnitude });
+            }
+        }
+        self.effects.retain(|effect| !effect.duration.finished());
+        ticks
+    }
+
+    /// Kinds currently active with how many stacks and how long the longest lasts.
+    pub fn summary(&self) -> Vec<(StatusKind, usize, Duration)> {
+        let mut summary: Vec<(StatusKind, usize, Duration)> = vec![];
+        for effect in self.effects.iter() {
+            match summary.iter_mut().find(|(kind, _, _)| *kind == effect.kind) {
+                Some((_, stacks, remaining)) => {
+                    *stacks += 1;
+                    *remaining = (*remaining).max(effect.remaining());
+                }
+                None => summary.push((effect.kind, 1, effect.remaining())),
+            }
+        }
+        summary
+    }
+}
+
+/// Applies its effect to whatever it touches that can carry status effects.
+#[derive(Component, Clone)]
+pub struct ContactEffect(pub StatusEffect);
+
+/// Effect of using an item from the inventory.
+pub fn item_effect(kind: ItemKind) -> Option<StatusEffect> {
+    let total = restore_amount(kind)?;
+    let duration = match kind {
+        ItemKind::Herbs => Duration::from_secs(4),
+        _ => Duration::from_secs(1),
+    };
+    Some(StatusEffect::regen(total, duration))
+}
+
+/// The weapon artifact is radioactive: activating it irradiates the ship for a while.
+pub fn artifact_side_effects(
+    mut artifact_events: EventReader<ArtifactActivatedEvent>,
+    mut ship_q: Query<&mut StatusEffects, With<Ship>>,
+) {
+    for ev in artifact_events.iter() {
+        if ev.0 != ItemKind::WeaponArtifact { continue; }
+        for mut status in ship_q.iter_mut() {
+            status.apply(StatusEffect::radiation(0.25, Duration::from_secs(8)));
+        }
+    }
+}
+
+pub fn apply_contact_effects(
+    mut collision_events: EventReader<CollisionEvent>,
+    contact_q: Query<&ContactEffect>,
+    mut status_q: Query<&mut StatusEffects, With<Ship>>,
+) {
+    for collision_event in collision_events.iter() {
+        let CollisionEvent::Started(e1, e2, _) = collision_event else { continue; };
+        for (source, target) in [(e1, e2), (e2, e1)] {
+            let Ok(contact) = contact_q.get(*source) else { continue; };
+            if let Ok(mut status) = status_q.get_mut(*target) {
+                status.apply(contact.0.clone());
+            }
+        }
+    }
+}
+
+pub fn tick_status_effects(
+    time: Res<Time>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut ev_damage_taken: EventWriter<DamageTakenEvent>,
+    mut status_q: Query<(&mut StatusEffects, Option<&mut Health>, Option<&Ship>)>,
+) {
+    for (mut status, health, ship) in status_q.iter_mut() {
+        if status.is_empty() { continue; }
+        let ticks = status.tick(time.delta());
+        let Some(mut health) = health else { continue; };
+        for tick in ticks {
+            match tick.kind.damage_source() {
+                Some(source) => {
+                    health.current -= tick.amount;
+                    if ship.is_some() {
+                        ev_damage_taken.send(DamageTakenEvent(tick.amount, source));
+                    }
+                }
+                None => health.current = (health.current + tick.amount).min(health.max),
+            }
+        }
+        if ship.is_some() && health.current <= 0. {
+            next_state.set(AppState::GameOver);
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct StatusIconRow;
+
+pub fn setup_status_ui(mut commands: Commands) {
+    commands.spawn((InGameUI, StatusIconRow, NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                right: Val::Px(8.),
+                top: Val::Px(8.),
+                ..default()
+            },
+            gap: Size::width(Val::Px(6.)),
+            ..default()
+        },
+        ..default()
+    }));
+}
+
+pub fn status_icons_ui(
+    mut commands: Commands,
+    mut shown: Local<Vec<(StatusKind, usize, u64)>>,
+    asset_server: Res<AssetServer>,
+    ship_q: Query<&StatusEffects, With<Ship>>,
+    row_q: Query<Entity, With<StatusIconRow>>,
+) {
+    let Ok(row) = row_q.get_single() else { return; };
+    let summary: Vec<(StatusKind, usize, u64)> = ship_q.get_single()
+        .map(|status| status.summary().into_iter()
+            .map(|(kind, stacks, remaining)| (kind, stacks, remaining.as_secs_f32().ceil() as u64))
+            .collect())
+        .unwrap_or_default();
+    if *shown == summary { return; }
+    commands.entity(row).despawn_descendants();
+    commands.entity(row).with_children(|parent| {
+        for (kind, stacks, seconds) in summary.iter() {
+            let stacks = if *stacks > 1 { format!(" x{}", stacks) } else { String::new() };
+            parent.spawn(NodeBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(4.)),
+                    ..default()
+                },
+                background_color: kind.color().with_a(0.85).into(),
+                ..default()
+            }).with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    format!("{}{} {}s", kind.label(), stacks, seconds),
+                    TextStyle {
+                        font: asset_server.load("fonts/static/JetBrainsMono-Regular.ttf"),
+                        font_size: 14.,
+                        color: Color::hex("#1B0A28").unwrap(),
+                    },
+                ));
+            });
+        }
+    });
+    *shown = summary;
+}
+
+#[cfg(test)]
+mod status_effects_tests {
+    use bevy_rapier2d::rapier::geometry::CollisionEventFlags;
+    use super::*;
+
+    #[test]
+    fn it_only_applies_contact_effects_to_the_ship() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_event::<CollisionEvent>();
+        app.add_system(apply_contact_effects);
+        let poison = || ContactEffect(StatusEffect::poison(0.5, Duration::from_secs(4)));
+        let organism = app.world.spawn((poison(), StatusEffects::default())).id();
+        let other_organism = app.world.spawn((poison(), StatusEffects::default())).id();
+        let ship = app.world.spawn((Ship, StatusEffects::default())).id();
+        app.world.send_event(CollisionEvent::Started(organism, other_organism, CollisionEventFlags::empty()));
+        app.world.send_event(CollisionEvent::Started(organism, ship, CollisionEventFlags::empty()));
+
+        app.update();
+
+        let stacks = |entity| app.world.entity(entity).get::<StatusEffects>().unwrap().stacks(StatusKind::Poison);
+        assert_eq!(stacks(organism), 0);
+        assert_eq!(stacks(other_organism), 0);
+        assert_eq!(stacks(ship), 1);
+    }
+
+    #[test]
+    fn it_stacks_up_to_the_limit_and_replaces_the_oldest() {
+        let mut status = StatusEffects::default();
+        for seconds in 1..=4 {
+            status.apply(StatusEffect::poison(1., Duration::from_secs(seconds)));
+        }
+        assert_eq!(status.stacks(StatusKind::Poison), 3);
+        let longest = status.summary()[0].2;
+        assert_eq!(longest, Duration::from_secs(4));
+    }
+
+    #[test]
+    fn it_refreshes_slows_without_compounding() {
+        let mut status = StatusEffects::default();
+        status.apply(StatusEffect::slow(0.3, Duration::from_secs(2)));
+        status.tick(Duration::from_secs(1));
+        status.apply(StatusEffect::slow(0.5, Duration::from_secs(2)));
+        assert_eq!(status.stacks(StatusKind::Slow), 1);
+        assert_eq!(status.speed_multiplier(), 0.5);
+        assert_eq!(status.summary()[0].2, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn it_ticks_until_the_effect_expires() {
+        let mut status = StatusEffects::default();
+        status.apply(StatusEffect::regen(2., Duration::from_secs(2)));
+        let healed: f32 = status.tick(Duration::from_secs(5)).iter().map(|tick| tick.amount).sum();
+        assert_eq!(healed, 2.);
+        assert!(status.is_empty());
+    }
+
+    #[test]
+    fn it_extends_regen_instead_of_stacking() {
+        let mut status = StatusEffects::default();
+        status.apply(item_effect(ItemKind::Herbs).unwrap());
+        status.apply(item_effect(ItemKind::Herbs).unwrap());
+        assert_eq!(status.stacks(StatusKind::Regen), 1);
+        let healed: f32 = status.tick(Duration::from_secs(10)).iter().map(|tick| tick.amount).sum();
+        assert_eq!(healed, 2. * restore_amount(ItemKind::Herbs).unwrap());
+    }
+}
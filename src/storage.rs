@@ -0,0 +1,48 @@
+use bevy::prelude::warn;
+
+/// Reads a previously saved `<key>.ron` file.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read(key: &str) -> Option<String> {
+    std::fs::read_to_string(format!("{}.ron", key)).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write(key: &str, text: &str) {
+    if let Err(error) = std::fs::write(format!("{}.ron", key), text) {
+        warn!("Could not save {}.ron: {}", key, error);
+    }
+}
+
+/// Seconds since the Unix epoch, used to date saved records.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Reads a previously saved value from the browser's localStorage.
+#[cfg(target_arch = "wasm32")]
+pub fn read(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn write(key: &str, text: &str) {
+    let saved = local_storage().map(|storage| storage.set_item(key, text).is_ok());
+    if saved != Some(true) {
+        warn!("Could not save {} to localStorage", key);
+    }
+}
+
+/// Seconds since the Unix epoch; `SystemTime` is unavailable in the browser, so ask JS instead.
+#[cfg(target_arch = "wasm32")]
+pub fn unix_time() -> u64 {
+    (js_sys::Date::now() / 1000.) as u64
+}
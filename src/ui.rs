@@ -3,11 +3,20 @@ use bevy::asset::AssetServer;
 use bevy::hierarchy::{BuildChildren, Children, DespawnRecursiveExt};
 use bevy_ecs_ldtk::LevelSelection;
 use crate::AppState;
-use crate::beams::{BeamUpEvent, UnderBeamItems};
-use crate::level::{Element251, Gold, Health, Herbs, Item, LightSpeed, Organism, ShieldArtifact, Water, WeaponArtifact, YellowOrganism};
+use crate::beams::{BeamEnergy, BeamLevel, BeamUpEvent, UnderBeamItems};
+use crate::boost::Boost;
+use crate::crafting::{ArtifactActivatedEvent, RECIPES, RecipeEffect, RecipeOutput};
+use crate::game_assets::GameAssets;
+use crate::leaderboard::{Leaderboard, score, spawn_leaderboard};
+use crate::level::{Health, Inventory, Item, LightSpeed};
+use crate::run::RunStart;
+use crate::settings::Settings;
+use crate::settings_menu::OpenSettingsButton;
 use crate::ship::Ship;
+use crate::stats::RunStats;
+use crate::status_effects::{item_effect, StatusEffects};
 
-pub fn create_main_menu(commands: &mut Commands, asset_server: &Res<AssetServer>) {
+pub fn create_main_menu(commands: &mut Commands, asset_server: &Res<AssetServer>, leaderboard: &Leaderboard) {
     commands.spawn((MainMenuUI, NodeBundle {
         style: Style {
             justify_content: JustifyContent::Center,
@@ -20,9 +29,10 @@ pub fn create_main_menu(commands: &mut Commands, asset_server: &Res<AssetServer>
         parent.spawn(NodeBundle {
             style: Style {
                 flex_direction: FlexDirection::Column,
-                size: Size::new(Val::Percent(100.), Val::Px(350.)),
+                size: Size::new(Val::Percent(100.), Val::Auto),
                 justify_content: JustifyContent::SpaceBetween,
                 align_items: AlignItems::Center,
+                gap: Size::height(Val::Px(24.)),
                 ..default()
             },
             ..default()
@@ -60,6 +70,26 @@ pub fn create_main_menu(commands: &mut Commands, asset_server: &Res<AssetServer>
                     },
                 ));
             });
+            parent.spawn((OpenSettingsButton, ButtonBundle {
+                style: Style {
+                    size: Size::UNDEFINED,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::NONE),
+                ..default()
+            })).with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    "Settings",
+                    TextStyle {
+                        font: asset_server.load("fonts/static/JetBrainsMono-Regular.ttf"),
+                        font_size: 32.,
+                        ..default()
+                    },
+                ));
+            });
+            spawn_leaderboard(parent, asset_server, leaderboard, false);
         });
     });
 }
@@ -79,7 +109,12 @@ pub fn clean_up_ui<T: Component>(
 #[derive(Component)]
 pub struct GameOverUI;
 
-pub fn setup_game_over(mut commands: Commands, asset_server: Res<AssetServer>) {
+pub fn setup_game_over(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    stats: Res<RunStats>,
+    leaderboard: Res<Leaderboard>,
+) {
     commands.insert_resource(LevelSelection::Index(1));
     commands.spawn((GameOverUI, NodeBundle {
         style: Style {
@@ -93,9 +128,10 @@ pub fn setup_game_over(mut commands: Commands, asset_server: Res<AssetServer>) {
         parent.spawn(NodeBundle {
             style: Style {
                 flex_direction: FlexDirection::Column,
-                size: Size::new(Val::Percent(100.), Val::Px(350.)),
+                size: Size::new(Val::Percent(100.), Val::Auto),
                 justify_content: JustifyContent::SpaceBetween,
                 align_items: AlignItems::Center,
+                gap: Size::height(Val::Px(16.)),
                 ..default()
             },
             ..default()
@@ -108,34 +144,104 @@ pub fn setup_game_over(mut commands: Commands, asset_server: Res<AssetServer>) {
                     color: Color::hex("#FFF").unwrap(),
                 },
             ));
-            parent.spawn((StartAdventureButton, ButtonBundle {
+            parent.spawn(TextBundle::from_section(
+                stats.cause_of_death(),
+                TextStyle {
+                    font: asset_server.load("fonts/static/JetBrainsMono-Regular.ttf"),
+                    font_size: 32.,
+                    color: Color::hex("#FF8A5B").unwrap(),
+                },
+            ));
+            let mut summary = stats.summary();
+            summary.insert(0, format!("Score: {}", score(&stats)));
+            parent.spawn(TextBundle::from_section(
+                summary.join("\n"),
+                TextStyle {
+                    font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+                    font_size: 20.,
+                    color: Color::hex("#FFF").unwrap(),
+                },
+            ).with_text_alignment(TextAlignment::Center));
+            parent.spawn(NodeBundle {
                 style: Style {
-                    size: Size::UNDEFINED,
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
+                    gap: Size::width(Val::Px(48.)),
                     ..default()
                 },
-                background_color: BackgroundColor(Color::Rgba {
-                    red: 0.0,
-                    green: 0.0,
-                    blue: 0.0,
-                    alpha: 0.0,
-                }),
                 ..default()
-            })).with_children(|parent| {
-                parent.spawn(TextBundle::from_section(
-                    "New Game",
-                    TextStyle {
-                        font: asset_server.load("fonts/static/JetBrainsMono-Regular.ttf"),
-                        font_size: 40.,
+            }).with_children(|parent| {
+                parent.spawn((RetryLevelButton, ButtonBundle {
+                    style: Style {
+                        size: Size::UNDEFINED,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
                         ..default()
                     },
-                ));
+                    background_color: BackgroundColor(Color::NONE),
+                    ..default()
+                })).with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Retry level",
+                        TextStyle {
+                            font: asset_server.load("fonts/static/JetBrainsMono-Regular.ttf"),
+                            font_size: 40.,
+                            ..default()
+                        },
+                    ));
+                });
+                parent.spawn((StartAdventureButton, ButtonBundle {
+                    style: Style {
+                        size: Size::UNDEFINED,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::NONE),
+                    ..default()
+                })).with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "New Game",
+                        TextStyle {
+                            font: asset_server.load("fonts/static/JetBrainsMono-Regular.ttf"),
+                            font_size: 40.,
+                            ..default()
+                        },
+                    ));
+                });
             });
+            spawn_leaderboard(parent, &asset_server, &leaderboard, true);
         });
     });
 }
 
+#[derive(Component)]
+pub struct RetryLevelButton;
+
+#[allow(clippy::type_complexity)]
+pub fn retry_level_interactions(
+    mut interaction_query: Query<(&Interaction, &Children),
+        (Changed<Interaction>, With<RetryLevelButton>)>,
+    mut text_query: Query<&mut Text>,
+    mut run_start: ResMut<RunStart>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, children) in &mut interaction_query {
+        let mut text = text_query.get_mut(children[0]).unwrap();
+        match *interaction {
+            Interaction::Clicked => {
+                *run_start = RunStart::RetryLevel;
+                next_state.set(AppState::InGame);
+            }
+            Interaction::Hovered => {
+                text.sections[0].value = "- Retry level -".to_string();
+            }
+            Interaction::None => {
+                text.sections[0].value = "Retry level".to_string();
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
 pub fn menu_button_interactions_system(
     mut interaction_query: Query<(&Interaction, &Children),
         (Changed<Interaction>, With<StartAdventureButton>)>,
@@ -167,29 +273,28 @@ struct DialogText;
 #[derive(Component)]
 pub struct DialogBox;
 
-#[derive(Component)]
-struct DialogContinueButton;
-
+#[allow(clippy::type_complexity)]
 pub fn dialog_interaction_system(
     mut interaction_query: Query<(&Interaction, &Children, &mut Style),
         (Changed<Interaction>, With<DialogBox>)>,
-    mut text_query: Query<&mut Text>,
+    mut typewriter_query: Query<&mut Typewriter>,
     mut next_state: ResMut<NextState<AppState>>,
     mut dialog_state: ResMut<DialogState>,
 ) {
     for (interaction, children, mut style) in interaction_query.iter_mut() {
-        let mut text = text_query.get_mut(children[0]).unwrap();
+        let mut typewriter = typewriter_query.get_mut(children[0]).unwrap();
         match *interaction {
             Interaction::Clicked => {
+                if !typewriter.finished() {
+                    typewriter.finish();
+                    continue;
+                }
                 dialog_state.0.pop();
-                text.sections[0].value =
-                    dialog_state.0.last().unwrap_or(&"".to_string()).to_string();
+                *typewriter = Typewriter::new(dialog_state.0.last().cloned().unwrap_or_default());
                 if dialog_state.0.is_empty() { next_state.set(AppState::InGame) };
             }
             Interaction::None => {
                 style.border = UiRect::all(Val::Px(0.));
-                text.sections[0].value =
-                    dialog_state.0.last().unwrap_or(&"".to_string()).to_string();
             }
             Interaction::Hovered => {
                 style.border = UiRect::all(Val::Px(2.));
@@ -198,17 +303,56 @@ pub fn dialog_interaction_system(
     }
 }
 
-
+/// Reveals `text` a few characters at a time, at the player's chosen text speed.
 #[derive(Component)]
-pub struct IntroUI;
+pub struct Typewriter {
+    text: String,
+    shown: f32,
+}
 
-pub fn load_level(
-    mut commands: Commands,
+impl Typewriter {
+    pub fn new(text: String) -> Self {
+        Typewriter { text, shown: 0. }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.shown as usize >= self.text.chars().count()
+    }
+
+    pub fn finish(&mut self) {
+        self.shown = self.text.chars().count() as f32;
+    }
+
+    pub fn visible_text(&self) -> String {
+        self.text.chars().take(self.shown as usize).collect()
+    }
+
+    pub fn advance(&mut self, seconds: f32, chars_per_second: Option<f32>) {
+        match chars_per_second {
+            Some(speed) => self.shown = (self.shown + seconds * speed).min(self.text.chars().count() as f32),
+            None => self.finish(),
+        }
+    }
+}
+
+pub fn typewriter_system(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut typewriter_query: Query<(&mut Typewriter, &mut Text)>,
 ) {
-    commands.insert_resource(LevelSelection::Index(0));
+    for (mut typewriter, mut text) in typewriter_query.iter_mut() {
+        typewriter.advance(time.raw_delta_seconds(), settings.text_speed.chars_per_second());
+        let visible = typewriter.visible_text();
+        if text.sections[0].value != visible {
+            text.sections[0].value = visible;
+        }
+    }
 }
 
 
+#[derive(Component)]
+pub struct IntroUI;
+
 pub fn setup_intro(
     mut commands: Commands,
     light_query: Query<Entity, With<LightSpeed>>,
@@ -225,6 +369,7 @@ pub fn setup_intro(
         "\"Its been a journey but I finally found material 251 on the planet 3.\"".to_string(),
         "Journal Entry: Day 1 - Click".to_string(),
     ]);
+    let first_line = dialog.0.last().cloned().unwrap_or_default();
     commands.insert_resource(dialog);
     for entity in light_query.iter() {
         commands.entity(entity).insert(Visibility::Visible);
@@ -249,7 +394,7 @@ pub fn setup_intro(
             background_color: BackgroundColor(Color::hex("#43374F").unwrap()),
             ..default()
         })).with_children(|parent| {
-            parent.spawn((DialogText, TextBundle::from_section(
+            parent.spawn((DialogText, Typewriter::new(first_line), TextBundle::from_section(
                 "".to_string(),
                 TextStyle {
                     font: asset_server.load("fonts/static/JetBrainsMono-Regular.ttf"),
@@ -283,10 +428,22 @@ pub struct InventoryPanel;
 pub struct HealthText;
 
 #[derive(Component)]
-pub struct InventoryText;
+pub struct BoostGaugeFill;
 
 #[derive(Component)]
-pub struct InventoryButton;
+pub struct BeamEnergyGaugeFill;
+
+#[derive(Component)]
+pub struct BeamUpgradeButton;
+
+#[derive(Component)]
+pub struct CraftingPanel;
+
+#[derive(Component)]
+pub struct CraftButton(pub usize);
+
+#[derive(Component)]
+pub struct InventoryButton(pub usize);
 
 #[derive(Resource)]
 pub struct PanelMainText(pub String);
@@ -315,8 +472,10 @@ pub fn setup_game_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
             parent.spawn(NodeBundle {
                 style: Style {
                     size: Size::new(Val::Percent(25.), Val::Percent(100.)),
+                    flex_direction: FlexDirection::Column,
                     justify_content: JustifyContent::Center,
                     align_items: AlignItems::Center,
+                    gap: Size::all(Val::Px(6.)),
                     ..default()
                 },
                 background_color: Color::rgba(27. / 255., 10. / 255., 40. / 255., 0.9).into(),
@@ -329,6 +488,24 @@ pub fn setup_game_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                         font_size: 18.,
                         ..default()
                     })));
+                spawn_gauge(parent, &asset_server, "Boost Fuel", BoostGaugeFill, Color::hex("#8FD3FF").unwrap());
+                spawn_gauge(parent, &asset_server, "Beam Energy", BeamEnergyGaugeFill, Color::hex("#F2D16B").unwrap());
+                parent.spawn((BeamUpgradeButton, ButtonBundle {
+                    background_color: Color::hex("#43374F").unwrap().into(),
+                    style: Style {
+                        padding: UiRect::new(Val::Px(6.), Val::Px(6.), Val::Px(2.), Val::Px(2.)),
+                        ..default()
+                    },
+                    ..default()
+                })).with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+                            font_size: 14.,
+                            ..default()
+                        }));
+                });
             });
             parent.spawn(NodeBundle {
                 style: Style {
@@ -361,109 +538,322 @@ pub fn setup_game_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
             }));
         });
     });
+    commands.spawn((InGameUI, CraftingPanel, NodeBundle {
+        style: Style {
+            display: Display::None,
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                right: Val::Px(0.),
+                top: Val::Px(0.),
+                ..default()
+            },
+            size: Size::new(Val::Px(320.), Val::Auto),
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(8.)),
+            gap: Size::all(Val::Px(4.)),
+            ..default()
+        },
+        background_color: Color::rgba(27. / 255., 10. / 255., 40. / 255., 0.9).into(),
+        ..default()
+    }));
+}
+
+fn spawn_gauge(parent: &mut ChildBuilder, asset_server: &Res<AssetServer>, label: &str, fill: impl Component, color: Color) {
+    parent.spawn(TextBundle::from_section(
+        label,
+        TextStyle {
+            font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+            font_size: 14.,
+            ..default()
+        }));
+    parent.spawn(NodeBundle {
+        style: Style {
+            size: Size::new(Val::Percent(70.), Val::Px(10.)),
+            ..default()
+        },
+        background_color: Color::hex("#43374F").unwrap().into(),
+        ..default()
+    }).with_children(|parent| {
+        parent.spawn((fill, NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                ..default()
+            },
+            background_color: color.into(),
+            ..default()
+        }));
+    });
 }
 
 pub fn inventory_interactions(
-    mut commands: Commands,
-    mut interaction_query: Query<(&Interaction, Entity, &Item), (Changed<Interaction>, With<InventoryButton>)>,
+    interaction_query: Query<(&Interaction, &InventoryButton, &Item), Changed<Interaction>>,
     mut panel_main_text: ResMut<PanelMainText>,
-    mut health_q: Query<&mut Health, With<Ship>>,
-    herb_q: Query<&Herbs>,
+    mut ship_q: Query<(&mut StatusEffects, &mut Inventory), With<Ship>>,
 ) {
-    for (interaction, entity, item) in interaction_query.iter_mut() {
+    for (interaction, button, item) in interaction_query.iter() {
         match *interaction {
             Interaction::Clicked => {
-                if herb_q.get(entity).is_ok() {
-                    for mut health in health_q.iter_mut() {
-                        if health.current < health.max {
-                            health.current += 1.;
-                        }
-                    }
+                // Items without an effect are kept: they're spent on upgrades and crafting instead.
+                let Some(effect) = item_effect(item.kind) else { continue; };
+                for (mut status, mut inventory) in ship_q.iter_mut() {
+                    status.apply(effect.clone());
+                    inventory.remove_at(button.0);
                 }
-                commands.entity(entity).despawn_recursive();
                 panel_main_text.0 = "".to_string();
             }
             Interaction::None => {
                 panel_main_text.0 = "".to_string();
             }
             Interaction::Hovered => {
-                panel_main_text.0 = format!("{}\nLeft-click to use.", item.description);
+                panel_main_text.0 = if item_effect(item.kind).is_some() {
+                    format!("{}\nLeft-click to use.", item.description)
+                } else {
+                    item.description.clone()
+                };
             }
         }
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn health_ui(
     health_q: Query<&Health, (Or<(Changed<Health>, Added<Health>)>, With<Ship>)>,
     mut text_q: Query<&mut Text, With<HealthText>>,
 ) {
     for health in health_q.iter() {
         for mut text in text_q.iter_mut() {
-            text.sections[0].value = format!("Life Support: {}/{}", health.current, health.max);
+            text.sections[0].value = format!("Life Support: {:.1}/{}", health.current.max(0.), health.max);
         }
     }
 }
 
-pub fn inventory_ui(
-    mut commands: Commands,
-    mut beam_up_event: EventReader<BeamUpEvent>,
-    inventory_panel_q: Query<Entity, With<InventoryPanel>>,
-    item_q: Query<&Item>,
-    herb_q: Query<&Herbs>,
-    org_q: Query<&Organism>,
-    yellow_q: Query<&YellowOrganism>,
-    gold_q: Query<&Gold>,
-    element_q: Query<&Element251>,
-    water_q: Query<&Water>,
-    weapon_q: Query<&WeaponArtifact>,
-    shield_q: Query<&ShieldArtifact>,
+pub fn boost_ui(
+    boost_q: Query<&Boost, (Changed<Boost>, With<Ship>)>,
+    mut gauge_q: Query<(&mut Style, &mut BackgroundColor), With<BoostGaugeFill>>,
 ) {
-    if let Ok(inventory_panel) = inventory_panel_q.get_single() {
-        for ev in beam_up_event.iter() {
-            if let Ok(item) = item_q.get(ev.0) {
-                let id = commands.spawn((
-                    InventoryButton,
-                    item.clone(),
-                    ButtonBundle {
-                        style: Style {
-                            padding: UiRect::new(Val::Px(2.), Val::Px(2.), Val::Px(2.), Val::Px(2.)),
-                            size: Size::new(Val::Px(24.), Val::Px(24.)),
-                            ..default()
-                        },
-                        image: UiImage::new(item.texture.clone()),
-                        ..default()
-                    })).id();
-                if let Ok(element) = herb_q.get(ev.0) {
-                    commands.entity(id).insert(element.clone());
-                }
-                if let Ok(element) = org_q.get(ev.0) {
-                    commands.entity(id).insert(element.clone());
-                }
-                if let Ok(element) = yellow_q.get(ev.0) {
-                    commands.entity(id).insert(element.clone());
-                }
-                if let Ok(element) = gold_q.get(ev.0) {
-                    commands.entity(id).insert(element.clone());
-                }
-                if let Ok(element) = element_q.get(ev.0) {
-                    commands.entity(id).insert(element.clone());
-                }
-                if let Ok(element) = water_q.get(ev.0) {
-                    commands.entity(id).insert(element.clone());
-                }
-                if let Ok(element) = weapon_q.get(ev.0) {
-                    commands.entity(id).insert(element.clone());
+    for boost in boost_q.iter() {
+        for (mut style, mut color) in gauge_q.iter_mut() {
+            style.size.width = Val::Percent(boost.fuel_percent());
+            *color = if boost.cooldown.finished() {
+                Color::hex("#8FD3FF").unwrap().into()
+            } else {
+                Color::hex("#A05B53").unwrap().into()
+            };
+        }
+    }
+}
+
+pub fn beam_energy_ui(
+    energy_q: Query<&BeamEnergy, (Changed<BeamEnergy>, With<Ship>)>,
+    mut gauge_q: Query<(&mut Style, &mut BackgroundColor), With<BeamEnergyGaugeFill>>,
+) {
+    for energy in energy_q.iter() {
+        for (mut style, mut color) in gauge_q.iter_mut() {
+            style.size.width = Val::Percent(energy.energy_percent());
+            *color = if energy.overheated() {
+                Color::hex("#A05B53").unwrap().into()
+            } else {
+                Color::hex("#F2D16B").unwrap().into()
+            };
+        }
+    }
+}
+
+pub fn beam_upgrade_interactions(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BeamUpgradeButton>)>,
+    mut panel_main_text: ResMut<PanelMainText>,
+    mut ship_q: Query<(&mut BeamLevel, &mut Inventory), With<Ship>>,
+) {
+    for interaction in interaction_query.iter() {
+        let Ok((mut level, mut inventory)) = ship_q.get_single_mut() else { continue; };
+        match *interaction {
+            Interaction::Clicked => {
+                if !level.try_upgrade(&mut inventory) {
+                    panel_main_text.0 = "Not enough resources to upgrade the beam.".to_string();
+                } else {
+                    panel_main_text.0 = "".to_string();
                 }
-                if let Ok(element) = shield_q.get(ev.0) {
-                    commands.entity(id).insert(element.clone());
+            }
+            Interaction::None => {
+                panel_main_text.0 = "".to_string();
+            }
+            Interaction::Hovered => {
+                panel_main_text.0 = beam_upgrade_description(&level);
+            }
+        }
+    }
+}
+
+fn beam_upgrade_description(level: &BeamLevel) -> String {
+    if level.is_max() { return "Beam is fully upgraded.".to_string(); }
+    let cost = level.config().upgrade_cost.iter()
+        .map(|(kind, count)| format!("{} {}", count, kind.name()))
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("Upgrade Beam:\nLonger range, stronger pull, more items at once.\nConsumes {} to upgrade.", cost)
+}
+
+pub fn beam_level_ui(
+    level_q: Query<Ref<BeamLevel>, With<Ship>>,
+    button_q: Query<(Ref<BeamUpgradeButton>, &Children)>,
+    mut text_q: Query<&mut Text>,
+) {
+    let Ok(level) = level_q.get_single() else { return; };
+    for (button, children) in button_q.iter() {
+        if !level.is_changed() && !button.is_added() { continue; }
+        if let Ok(mut text) = text_q.get_mut(children[0]) {
+            text.sections[0].value = if level.is_max() {
+                format!("Beam Lvl {} (Max)", level.0 + 1)
+            } else {
+                format!("Upgrade Beam [Lvl {}]", level.0 + 1)
+            };
+        }
+    }
+}
+
+pub fn toggle_crafting_panel(
+    key_input: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    mut panel_q: Query<&mut Style, With<CraftingPanel>>,
+) {
+    if !key_input.just_pressed(settings.bindings.crafting) { return; }
+    for mut style in panel_q.iter_mut() {
+        style.display = match style.display {
+            Display::None => Display::Flex,
+            Display::Flex => Display::None,
+        };
+    }
+}
+
+pub fn crafting_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    inventory_q: Query<Ref<Inventory>, With<Ship>>,
+    crafting_panel_q: Query<(Entity, Ref<CraftingPanel>)>,
+) {
+    let (Ok(inventory), Ok((crafting_panel, panel))) = (inventory_q.get_single(), crafting_panel_q.get_single()) else { return; };
+    if !inventory.is_changed() && !panel.is_added() { return; }
+    commands.entity(crafting_panel).despawn_descendants();
+    commands.entity(crafting_panel).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            format!("Crafting ({:?})", settings.bindings.crafting),
+            TextStyle {
+                font: asset_server.load("fonts/static/JetBrainsMono-Regular.ttf"),
+                font_size: 18.,
+                ..default()
+            }));
+        for (index, recipe) in RECIPES.iter().enumerate() {
+            let color = if recipe.can_craft(&inventory) {
+                Color::hex("#FFF").unwrap()
+            } else {
+                Color::hex("#6E6577").unwrap()
+            };
+            parent.spawn((CraftButton(index), ButtonBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(4.)),
+                    ..default()
+                },
+                background_color: Color::hex("#43374F").unwrap().into(),
+                ..default()
+            })).with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    format!("{}\n{}", recipe.name, recipe.ingredients_text()),
+                    TextStyle {
+                        font: asset_server.load("fonts/static/JetBrainsMono-Light.ttf"),
+                        font_size: 14.,
+                        color,
+                    }));
+            });
+        }
+    });
+}
+
+pub fn crafting_interactions(
+    game_assets: Res<GameAssets>,
+    interaction_query: Query<(&Interaction, &CraftButton), Changed<Interaction>>,
+    mut panel_main_text: ResMut<PanelMainText>,
+    mut ship_q: Query<(&mut Inventory, &mut Health, &mut Boost, &mut BeamEnergy), With<Ship>>,
+    mut ev_artifact: EventWriter<ArtifactActivatedEvent>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        let recipe = &RECIPES[button.0];
+        let Ok((mut inventory, mut health, mut boost, mut energy)) = ship_q.get_single_mut() else { continue; };
+        match *interaction {
+            Interaction::Clicked => {
+                let Some(output) = recipe.craft(&mut inventory) else {
+                    panel_main_text.0 = format!("Missing ingredients for {}.", recipe.name);
+                    continue;
+                };
+                match output {
+                    // Crafting just freed the inputs' slots, so the output always fits.
+                    RecipeOutput::Item(kind) => { inventory.add(&Item::from_kind(kind, &game_assets)); }
+                    RecipeOutput::Effect(RecipeEffect::RestoreLifeSupport(amount)) => {
+                        health.current = (health.current + amount).min(health.max);
+                    }
+                    RecipeOutput::Effect(RecipeEffect::RefillBoost) => boost.fuel = boost.max_fuel,
+                    RecipeOutput::Effect(RecipeEffect::CoolBeam) => energy.cool_down(),
+                    RecipeOutput::Effect(RecipeEffect::ActivateArtifact(kind)) => ev_artifact.send(ArtifactActivatedEvent(kind)),
                 }
-                commands.entity(ev.0).despawn_recursive();
-                commands.entity(inventory_panel).add_child(id);
+                panel_main_text.0 = format!("Crafted {}.", recipe.name);
+            }
+            Interaction::None => {
+                panel_main_text.0 = "".to_string();
+            }
+            Interaction::Hovered => {
+                let missing = recipe.missing_ingredients(&inventory);
+                panel_main_text.0 = if missing.is_empty() {
+                    format!("{}:\n{}\nLeft-click to craft.", recipe.name, recipe.description)
+                } else {
+                    let missing = missing.iter()
+                        .map(|(kind, count)| format!("{} {}", count, kind.name()))
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    format!("{}:\n{}\nMissing: {}.", recipe.name, recipe.description, missing)
+                };
             }
         }
     }
 }
 
+pub fn artifact_activated_ui(
+    mut ev_artifact: EventReader<ArtifactActivatedEvent>,
+    mut panel_main_text: ResMut<PanelMainText>,
+) {
+    for ev in ev_artifact.iter() {
+        panel_main_text.0 = format!("\"The {} hums to life!\"\n-Bob", ev.0.name());
+    }
+}
+
+pub fn inventory_ui(
+    mut commands: Commands,
+    mut beam_up_event: EventReader<BeamUpEvent>,
+    inventory_q: Query<Ref<Inventory>, With<Ship>>,
+    inventory_panel_q: Query<(Entity, Ref<InventoryPanel>)>,
+) {
+    for ev in beam_up_event.iter() {
+        commands.entity(ev.0).despawn_recursive();
+    }
+    let (Ok(inventory), Ok((inventory_panel, panel))) = (inventory_q.get_single(), inventory_panel_q.get_single()) else { return; };
+    if !inventory.is_changed() && !panel.is_added() { return; }
+    commands.entity(inventory_panel).despawn_descendants();
+    for (index, item) in inventory.iter().enumerate() {
+        let id = commands.spawn((
+            InventoryButton(index),
+            item.clone(),
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::new(Val::Px(2.), Val::Px(2.), Val::Px(2.), Val::Px(2.)),
+                    size: Size::new(Val::Px(24.), Val::Px(24.)),
+                    ..default()
+                },
+                image: UiImage::new(item.texture.clone()),
+                ..default()
+            })).id();
+        commands.entity(inventory_panel).add_child(id);
+    }
+}
+
 pub fn panel_text_update(
     mut panel_query: Query<&mut Text, With<PanelText>>,
     under_beam: Res<UnderBeamItems>,
@@ -476,8 +866,54 @@ pub fn panel_text_update(
         } else {
             let item = under_beam.0.last().unwrap();
             if let Ok(herb) = herb_query.get(*item) {
-                text.sections[0].value = format!("{}\nHit Spacebar to beam up.", herb.description);
+                text.sections[0].value = format!("{}\nHold the beam on it to pull it in.", herb.description);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod ui_tests {
+    use crate::level::ItemKind;
+    use super::*;
+
+    #[test]
+    fn it_reveals_dialog_text_over_time() {
+        let mut typewriter = Typewriter::new("Hello".to_string());
+        typewriter.advance(0.1, Some(20.));
+        assert_eq!(typewriter.visible_text(), "He");
+        assert!(!typewriter.finished());
+        typewriter.advance(1., Some(20.));
+        assert_eq!(typewriter.visible_text(), "Hello");
+        assert!(typewriter.finished());
+    }
+
+    #[test]
+    fn it_shows_dialog_text_at_once_when_instant() {
+        let mut typewriter = Typewriter::new("Hello".to_string());
+        typewriter.advance(0., None);
+        assert_eq!(typewriter.visible_text(), "Hello");
+    }
+
+    #[test]
+    fn it_keeps_items_that_have_no_use() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.insert_resource(PanelMainText(String::new()));
+        app.add_system(inventory_interactions);
+        let mut inventory = Inventory::new(10);
+        for kind in [ItemKind::Gold, ItemKind::Herbs] {
+            inventory.add(&Item { kind, description: String::new(), texture: Handle::default() });
+        }
+        let ship = app.world.spawn((Ship, StatusEffects::default(), inventory)).id();
+        let gold = app.world.spawn((Interaction::Clicked, InventoryButton(0), Item { kind: ItemKind::Gold, description: String::new(), texture: Handle::default() })).id();
+
+        app.update();
+        assert_eq!(app.world.entity(ship).get::<Inventory>().unwrap().iter().len(), 2);
+
+        app.world.despawn(gold);
+        app.world.spawn((Interaction::Clicked, InventoryButton(1), Item { kind: ItemKind::Herbs, description: String::new(), texture: Handle::default() }));
+        app.update();
+        assert_eq!(app.world.entity(ship).get::<Inventory>().unwrap().count(ItemKind::Herbs), 0);
+    }
+}